@@ -1 +1,3 @@
-pub mod sat_lib;
\ No newline at end of file
+extern crate rand;
+
+pub mod sat_lib;
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use sat_lib::Lit;
+
+//reference of a clause, the offset of its header in the arena
+pub type CRef = u32;
+
+//number of slots before the literals of a clause: its length, and its flags together with its LBD
+const HEADER: usize = 2;
+const LEARNT: u32 = 1;
+const DELETED: u32 = 2;
+const LBD_SHIFT: u32 = 2;
+
+#[derive (Debug)]
+//Clauses of the solver, stored one after another in a single vector. Each clause is a header
+//followed by its literals, and is referenced by the offset of its header. The removed clauses
+//stay in place until the garbage is collected, which moves the other clauses to the front.
+pub struct Cnf {
+	arena: 		Vec<Lit>,	//headers and literals of the clauses, a header slot keeps a number as the code of a literal
+	wasted: 	usize,		//number of slots taken by the removed clauses
+	antecedents:HashMap<CRef, Vec<CRef>>,	//clauses a learnt clause is derived from, if the core is tracked
+}
+
+impl Cnf {
+	pub fn new() -> Self {
+		Cnf {
+			arena: 		Vec::<Lit>::new(),
+			wasted: 	0,
+			antecedents:HashMap::<CRef, Vec<CRef>>::new(),
+		}
+	}
+	
+	//add a clause at the end of the arena and return its reference
+	pub fn add_clause(&mut self, lits: &[Lit], learnt: bool) -> CRef {
+		//every slot must be referenced by a CRef
+		assert!(self.arena.len() + HEADER + lits.len() <= CRef::MAX as usize, "The clause arena is full");
+		let cr = self.arena.len() as CRef;
+		self.arena.push(Lit { code: lits.len() as u32 });
+		self.arena.push(Lit { code: if learnt {LEARNT} else {0} });
+		self.arena.extend_from_slice(lits);
+		cr
+	}
+	
+	//references of the clauses which are not removed, in the order they were added
+	pub fn refs(&self) -> Vec<CRef> {
+		let mut refs = Vec::<CRef>::new();
+		let mut i = 0;
+		while i < self.arena.len() {
+			if self.arena[i + 1].code & DELETED == 0 {
+				refs.push(i as CRef);
+			}
+			i += HEADER + self.arena[i].code as usize;
+		}
+		refs
+	}
+	
+	pub fn lits(&self, cr: CRef) -> &[Lit] {
+		let start = cr as usize + HEADER;
+		&self.arena[start..start + self.arena[cr as usize].code as usize]
+	}
+	
+	pub fn lits_mut(&mut self, cr: CRef) -> &mut [Lit] {
+		let start = cr as usize + HEADER;
+		let end = start + self.arena[cr as usize].code as usize;
+		&mut self.arena[start..end]
+	}
+	
+	pub fn is_learnt(&self, cr: CRef) -> bool {
+		self.arena[cr as usize + 1].code & LEARNT != 0
+	}
+	
+	pub fn is_deleted(&self, cr: CRef) -> bool {
+		self.arena[cr as usize + 1].code & DELETED != 0
+	}
+	
+	//number of distinct decision levels in a learnt clause when it is learnt
+	pub fn lbd(&self, cr: CRef) -> usize {
+		(self.arena[cr as usize + 1].code >> LBD_SHIFT) as usize
+	}
+	
+	pub fn set_lbd(&mut self, cr: CRef, lbd: usize) {
+		let flags = &mut self.arena[cr as usize + 1].code;
+		*flags = *flags & (LEARNT | DELETED) | (lbd as u32) << LBD_SHIFT;
+	}
+	
+	pub fn antecedents(&self, cr: CRef) -> Option<&Vec<CRef>> {
+		self.antecedents.get(&cr)
+	}
+	
+	pub fn set_antecedents(&mut self, cr: CRef, antecedents: Vec<CRef>) {
+		self.antecedents.insert(cr, antecedents);
+	}
+	
+	//mark a clause as removed, its slots are reclaimed by the next garbage collection
+	pub fn delete(&mut self, cr: CRef) {
+		self.arena[cr as usize + 1].code |= DELETED;
+		self.wasted += HEADER + self.arena[cr as usize].code as usize;
+	}
+	
+	//number of slots in the arena, every reference is below it
+	pub fn size(&self) -> usize {
+		self.arena.len()
+	}
+	
+	pub fn wasted(&self) -> usize {
+		self.wasted
+	}
+	
+	//move the clauses which are not removed to the front of the arena, keeping their order
+	//return the old and the new reference of each of them, sorted by the old one
+	//the antecedents are not moved, so it must not be done while the core is tracked
+	pub fn collect_garbage(&mut self) -> Vec<(CRef, CRef)> {
+		let mut moved = Vec::<(CRef, CRef)>::new();
+		let mut arena = Vec::<Lit>::with_capacity(self.arena.len() - self.wasted);
+		for cr in self.refs() {
+			let start = cr as usize;
+			let end = start + HEADER + self.arena[start].code as usize;
+			moved.push((cr, arena.len() as CRef));
+			arena.extend_from_slice(&self.arena[start..end]);
+		}
+		self.arena = arena;
+		self.wasted = 0;
+		moved
+	}
+}
+
+//new reference of a clause moved by the garbage collection, None if it was removed
+pub fn relocate(moved: &[(CRef, CRef)], cr: CRef) -> Option<CRef> {
+	moved.binary_search_by_key(&cr, |&(old, _)| old).ok().map(|i| moved[i].1)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Cnf, relocate};
+	use sat_lib::{Lit, Var, MAX_VAR};
+	
+	fn lits(nums: &[i64]) -> Vec<Lit> {
+		nums.iter().map(|&n| Lit::from_dimacs(n).unwrap()).collect()
+	}
+	
+	#[test]
+	fn collect_garbage() {
+		let mut cnf = Cnf::new();
+		let a = cnf.add_clause(&lits(&[1, -2, 3]), false);
+		let b = cnf.add_clause(&lits(&[-1, 4]), true);
+		let c = cnf.add_clause(&lits(&[2, 5, -6, 7]), false);
+		cnf.delete(b);
+		assert!(cnf.is_deleted(b));
+		assert_eq!(cnf.wasted(), 4);
+		assert_eq!(cnf.refs(), vec![a, c]);
+		
+		let moved = cnf.collect_garbage();
+		assert_eq!(cnf.wasted(), 0);
+		assert_eq!(cnf.size(), 11);
+		assert_eq!(relocate(&moved, a), Some(0));
+		assert_eq!(relocate(&moved, b), None);
+		assert_eq!(relocate(&moved, c), Some(5));
+		assert_eq!(cnf.refs(), vec![0, 5]);
+		assert_eq!(cnf.lits(0), &lits(&[1, -2, 3])[..]);
+		assert_eq!(cnf.lits(5), &lits(&[2, 5, -6, 7])[..]);
+		assert!(!cnf.is_learnt(5) && !cnf.is_deleted(5));
+	}
+	
+	#[test]
+	fn header_flags() {
+		let mut cnf = Cnf::new();
+		let a = cnf.add_clause(&lits(&[1, 2]), true);
+		let b = cnf.add_clause(&lits(&[-1, 2]), false);
+		assert!(cnf.is_learnt(a) && !cnf.is_learnt(b));
+		assert_eq!(cnf.lbd(a), 0);
+		
+		//the LBD is kept above the flags, and does not change them
+		cnf.set_lbd(a, 7);
+		assert_eq!(cnf.lbd(a), 7);
+		assert!(cnf.is_learnt(a) && !cnf.is_deleted(a));
+		cnf.delete(a);
+		assert_eq!(cnf.lbd(a), 7);
+		assert!(cnf.is_learnt(a) && cnf.is_deleted(a));
+		cnf.set_lbd(a, 3);
+		assert_eq!(cnf.lbd(a), 3);
+		assert!(cnf.is_learnt(a) && cnf.is_deleted(a));
+		assert_eq!(cnf.lits(a), &lits(&[1, 2])[..]);
+		assert!(!cnf.is_deleted(b));
+	}
+	
+	#[test]
+	fn largest_var() {
+		let lit = Lit::new(Var::new(MAX_VAR));
+		assert_eq!(lit.var_num(), MAX_VAR);
+		assert_eq!((!lit).var_num(), MAX_VAR);
+	}
+	
+	#[test]
+	#[should_panic]
+	fn var_out_of_range() {
+		Var::new(MAX_VAR + 1);
+	}
+}
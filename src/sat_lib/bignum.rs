@@ -0,0 +1,158 @@
+use std::fmt;
+use std::ops::{Add, Mul};
+
+#[derive (Debug, Clone, PartialEq, Eq, Hash, Default)]
+///Unsigned integer of arbitrary size, used for model counts.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///let n = BigNum::from(1u64 << 40);
+///let m = &n * &n;
+///
+///println!("{}", m.shl(1)); //2417851639229258349412352
+///println!("{:?}", (&n + &BigNum::from(1)).to_u64()); //Some(1099511627777)
+///```
+pub struct BigNum {
+	limbs: 	Vec<u32>,	//little endian digits in base 2^32, without leading zeros
+}
+
+impl BigNum {
+	///Create zero
+	pub fn zero() -> Self {
+		BigNum {
+			limbs: 	Vec::<u32>::new(),
+		}
+	}
+	
+	///Create one
+	pub fn one() -> Self {
+		BigNum::from(1)
+	}
+	
+	///Check if the number is zero
+	pub fn is_zero(&self) -> bool {
+		self.limbs.is_empty()
+	}
+	
+	///Return the number if it fits in u64
+	pub fn to_u64(&self) -> Option<u64> {
+		match self.limbs.len() {
+			0 => Some(0),
+			1 => Some(self.limbs[0] as u64),
+			2 => Some(self.limbs[0] as u64 | (self.limbs[1] as u64) << 32),
+			_ => None,
+		}
+	}
+	
+	///Multiply the number by 2^bits
+	pub fn shl(&self, bits: usize) -> Self {
+		if self.is_zero() {
+			return BigNum::zero();
+		}
+		let mut limbs = vec![0; bits / 32];
+		let shift = bits % 32;
+		let mut carry = 0u32;
+		for &limb in &self.limbs {
+			if shift == 0 {
+				limbs.push(limb);
+			}else {
+				limbs.push(limb << shift | carry);
+				carry = limb >> (32 - shift);
+			}
+		}
+		if carry != 0 {
+			limbs.push(carry);
+		}
+		BigNum { limbs }
+	}
+	
+	//remove the leading zeros
+	fn trim(mut self) -> Self {
+		while self.limbs.last() == Some(&0) {
+			self.limbs.pop();
+		}
+		self
+	}
+	
+	//divide by a small number in place, and return the remainder
+	fn div_rem(&mut self, divisor: u32) -> u32 {
+		let mut rem = 0u64;
+		for limb in self.limbs.iter_mut().rev() {
+			let cur = rem << 32 | *limb as u64;
+			*limb = (cur / divisor as u64) as u32;
+			rem = cur % divisor as u64;
+		}
+		while self.limbs.last() == Some(&0) {
+			self.limbs.pop();
+		}
+		rem as u32
+	}
+}
+
+impl From<u64> for BigNum {
+	fn from(num: u64) -> Self {
+		BigNum {
+			limbs: 	vec![num as u32, (num >> 32) as u32],
+		}.trim()
+	}
+}
+
+impl Add for &BigNum {
+	type Output = BigNum;
+	
+	fn add(self, other: &BigNum) -> BigNum {
+		let mut limbs = Vec::<u32>::with_capacity(self.limbs.len().max(other.limbs.len()) + 1);
+		let mut carry = 0u64;
+		for i in 0..self.limbs.len().max(other.limbs.len()) {
+			let sum = *self.limbs.get(i).unwrap_or(&0) as u64 + *other.limbs.get(i).unwrap_or(&0) as u64 + carry;
+			limbs.push(sum as u32);
+			carry = sum >> 32;
+		}
+		if carry != 0 {
+			limbs.push(carry as u32);
+		}
+		BigNum { limbs }
+	}
+}
+
+impl Mul for &BigNum {
+	type Output = BigNum;
+	
+	fn mul(self, other: &BigNum) -> BigNum {
+		if self.is_zero() || other.is_zero() {
+			return BigNum::zero();
+		}
+		let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+		for (i, &a) in self.limbs.iter().enumerate() {
+			let mut carry = 0u64;
+			for (j, &b) in other.limbs.iter().enumerate() {
+				let cur = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+				limbs[i + j] = cur as u32;
+				carry = cur >> 32;
+			}
+			limbs[i + other.limbs.len()] = carry as u32;
+		}
+		BigNum { limbs }.trim()
+	}
+}
+
+impl fmt::Display for BigNum {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.is_zero() {
+			return write!(f, "0");
+		}
+		//digits in base 10^9, from the lowest
+		let mut num = self.clone();
+		let mut digits = Vec::<u32>::new();
+		while !num.is_zero() {
+			digits.push(num.div_rem(1_000_000_000));
+		}
+		write!(f, "{}", digits.pop().unwrap())?;
+		for d in digits.iter().rev() {
+			write!(f, "{:09}", d)?;
+		}
+		Ok(())
+	}
+}
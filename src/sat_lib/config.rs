@@ -0,0 +1,197 @@
+use std::time::Duration;
+
+#[derive (Debug, Copy, Clone, PartialEq, Eq)]
+///Strategy used to pick the next decision variable.
+pub enum Branching {
+	///Pick the unassigned variable with the highest activity (VSIDS).
+	///Variables in recent conflicts are bumped and all activities decay over time.
+	Vsids,
+	///Pick the unassigned variable with the smallest index.
+	Ordered,
+}
+
+#[derive (Debug, Copy, Clone, PartialEq)]
+///Strategy used to restart the search. A restart undoes every decision but keeps the learnt clauses,
+///the activities of variables and the saved phases. There is at least one conflict between two restarts.
+pub enum Restart {
+	///Never restart.
+	Never,
+	///Restart after `unit * luby(i)` conflicts, where `luby(i)` is the i-th element of the Luby sequence
+	///1, 1, 2, 1, 1, 2, 4, ...
+	Luby {
+		unit: 	usize,
+	},
+	///Restart after `first` conflicts, then multiply the interval by `factor` after every restart.
+	Geometric {
+		first: 	usize,
+		factor: f64,
+	},
+	///Glucose style restart. Restart when the average LBD of the last `window` learnt clauses,
+	///multiplied by `k`, exceeds the average LBD of all learnt clauses.
+	Glucose {
+		window: usize,
+		k: 		f64,
+	},
+}
+
+#[derive (Debug, Clone)]
+///Configuration of the solver.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///let mut config = SolverConfig::default();
+///config.branching = Branching::Ordered;
+///config.restart = Restart::Glucose { window: 50, k: 0.8 };
+///
+///let mut solver = Solver::with_config(config);
+///let x = Lit::create_lits(&solver.create_vars(2));
+///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+///
+///println!("{:?}", solver.solve()); //Sat
+///```
+pub struct SolverConfig {
+	///Strategy used to pick the next decision variable.
+	pub branching: 	Branching,
+	///Factor the activity of variables decays by after each conflict, used by VSIDS. Should be in (0, 1].
+	pub var_decay: 	f64,
+	///If a decision variable takes the value it had when it was last unassigned.
+	pub phase_saving: 	bool,
+	///Strategy used to restart the search.
+	pub restart: 	Restart,
+	///Number of learnt clauses kept before the first reduction of the learnt clause database.
+	pub reduce_first: 	usize,
+	///Number of learnt clauses the limit grows by after every reduction.
+	pub reduce_inc: 	usize,
+	///Number of conflicts between two calls of SolverObserver::on_progress. 0 disables them.
+	pub progress_interval: 	u64,
+	///If the clauses each learnt clause is derived from are recorded, so that Solver::get_unsat_core
+	///can find the original clauses responsible for UNSAT. It costs memory for every conflict.
+	pub core_tracking: 	bool,
+	///If simplify eliminates variables by resolving their clauses with each other, when it does not
+	///increase the number of clauses (bounded variable elimination). The model is extended to the
+	///eliminated variables. Not done while the core is tracked or a proof is written.
+	pub elimination: 	bool,
+	///If simplify removes the original clauses which contain all the literals of another clause, and
+	///removes a literal from a clause when the resolvent with another clause subsumes it (self-subsuming
+	///resolution). Not done while the core is tracked.
+	pub subsumption: 	bool,
+	///If simplify assigns each literal in turn and propagates it. A literal which leads to a conflict
+	///is learnt false, a literal implied by both values of a variable is learnt true, and the literals
+	///implied through longer clauses get binary clauses (hyper-binary resolution). Not done while the
+	///core is tracked.
+	pub probing: 		bool,
+	///If simplify finds the literals which imply each other through binary clauses, and replaces
+	///each of them by one representative. The model is extended to the replaced variables. Not done
+	///while the core is tracked or a proof is written.
+	pub equivalence: 	bool,
+	///If simplify removes the clauses of the pure literals, and the blocked clauses: the clauses
+	///with a literal whose resolvents are all tautologies. The model is repaired to satisfy them.
+	///Not done while the core is tracked or a proof is written.
+	pub blocked_clauses: 	bool,
+	///Number of conflicts between two vivifications, which run at the next restart. Each clause
+	///longer than two gets the negations of its literals assigned one by one, and is cut to the
+	///literals assigned before a conflict or a true literal is found. 0 disables it. Not done while
+	///the core is tracked.
+	pub vivify_interval: 	u64,
+}
+
+impl Default for SolverConfig {
+	fn default() -> Self {
+		SolverConfig {
+			branching: 	Branching::Vsids,
+			var_decay: 	0.95,
+			phase_saving: 	true,
+			restart: 	Restart::Luby { unit: 100 },
+			reduce_first: 	2000,
+			reduce_inc: 	300,
+			progress_interval: 	1000,
+			core_tracking: 	false,
+			elimination: 	false,
+			subsumption: 	false,
+			probing: 		false,
+			equivalence: 	false,
+			blocked_clauses: 	false,
+			vivify_interval: 	0,
+		}
+	}
+}
+
+#[derive (Debug, Copy, Clone, PartialEq)]
+///Variant of the stochastic local search run by LocalSearch. Both pick a random falsified clause
+///and flip one of its variables, chosen by the number of satisfied clauses the flip would break.
+pub enum SlsAlgorithm {
+	///Flip a variable breaking no clause if there is one. Otherwise flip a random variable of the
+	///clause with probability `noise`, and one breaking the fewest clauses else.
+	WalkSat {
+		noise: 	f64,
+	},
+	///Flip a variable with probability proportional to `cb ^ -break`.
+	ProbSat {
+		cb: 	f64,
+	},
+}
+
+#[derive (Debug, Copy, Clone, PartialEq)]
+///Configuration of the stochastic local search.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///let config = SlsConfig {
+///    algorithm: SlsAlgorithm::ProbSat { cb: 2.06 },
+///    seed: 42,
+///    ..Default::default()
+///};
+///```
+pub struct SlsConfig {
+	///Variant of the search and its noise parameter.
+	pub algorithm: 	SlsAlgorithm,
+	///Seed of the random number generator, the same seed gives the same search.
+	pub seed: 		u64,
+	///Number of flips from a random assignment before the search restarts.
+	pub max_flips: 	u64,
+	///Number of random assignments the search starts from before it gives up.
+	pub max_tries: 	u64,
+}
+
+impl Default for SlsConfig {
+	fn default() -> Self {
+		SlsConfig {
+			algorithm: 	SlsAlgorithm::WalkSat { noise: 0.567 },
+			seed: 		0,
+			max_flips: 	100000,
+			max_tries: 	10,
+		}
+	}
+}
+
+#[derive (Debug, Copy, Clone, Default, PartialEq, Eq)]
+///Limits on the work of one call of solve. None means no limit. When a limit is reached,
+///the search stops and solve returns SolveResult::Unknown.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///use std::time::Duration;
+///
+///let mut solver = Solver::new();
+///solver.set_budget(Budget {
+///    conflicts: Some(10000),
+///    time: Some(Duration::from_millis(50)),
+///    ..Default::default()
+///});
+///```
+pub struct Budget {
+	///Maximum number of conflicts.
+	pub conflicts: 		Option<u64>,
+	///Maximum number of decisions.
+	pub decisions: 		Option<u64>,
+	///Maximum number of propagated literals.
+	pub propagations: 	Option<u64>,
+	///Maximum elapsed time.
+	pub time: 			Option<Duration>,
+}
@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+use sat_lib::{Clause, Lit, Var, VarValue, BigNum};
+use sat_lib::VarValue::*;
+
+#[derive (Debug)]
+///Exact model counter (#SAT). The CNF is split into components which share no variable, whose counts
+///are multiplied, and the count of every component is cached.
+///
+///If a projection is set, the models are counted on the projection variables only, i.e. two models
+///which only differ on the other variables are counted once.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///let mut solver = Solver::new();
+///let v = solver.create_vars(3);
+///let x = Lit::create_lits(&v);
+///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+///
+///let mut counter = ModelCounter::new(&solver.get_oringin_clauses(), 3);
+///println!("{}", counter.count()); //6
+///
+///counter.set_projection(&v[..2]);
+///println!("{}", counter.count()); //3
+///```
+pub struct ModelCounter {
+	clauses: 	Vec<Vec<Lit>>,				//clauses without tautologies or duplicate literals
+	occurs: 	Vec<Vec<usize>>,			//clauses containing each literal
+	num_var: 	usize,
+	projected: 	Vec<bool>,					//if each variable is counted
+	value: 		Vec<VarValue>,				//current assignment
+	trail: 		Vec<usize>,					//assigned variables
+	parent: 	Vec<usize>,					//union find over the variables, MAX if unused
+	component: 	Vec<usize>,					//component of each root of the union find, MAX if none
+	score: 		Vec<usize>,					//occurrences of each variable in a component
+	cache: 		HashMap<Vec<u32>, BigNum>,	//count of each component, keyed by its variables and clauses
+	cache_len: 	usize,						//total length of the cache keys
+	unsat: 		bool,						//if the CNF has an empty clause
+}
+
+//the cache is cleared when its keys have more words than this
+const CACHE_LIMIT: usize = 1 << 25;
+
+//Step of the count kept on the stack of count_formula
+enum Frame {
+	Formula(FormulaFrame),
+	Component(ComponentFrame),
+}
+
+//formula whose components are counted one after another, and multiplied
+struct FormulaFrame {
+	res: 		BigNum,							//product of the counts so far
+	components: Vec<(Vec<usize>, Vec<usize>)>,	//clauses and variables of the components left
+}
+
+//component counted by branching on a variable, first on its positive literal
+struct ComponentFrame {
+	key: 	Vec<u32>,		//key of the component in the cache
+	ids: 	Vec<usize>,		//clauses of the component
+	vars: 	Vec<usize>,		//variables of the component, sorted
+	var: 	usize,			//variable branched on
+	start: 	usize,			//position of the trail before the branch
+	first: 	bool,			//if the first branch is being counted
+	res: 	BigNum,			//sum of the counts of the branches so far
+}
+
+impl ModelCounter {
+	///Create a counter of the models of the clauses over num_var variables
+	pub fn new(clauses: &[Clause], num_var: usize) -> Self {
+		let mut num_var = num_var;
+		let mut lits = Vec::<Vec<Lit>>::new();
+		let mut unsat = false;
+		for clause in clauses {
+			if let Some(max) = clause.get_max() {
+				num_var = num_var.max(max + 1);
+			}
+			let mut c = Vec::<Lit>::new();
+			let mut tautology = false;
+			for &lit in &clause.vec_lit {
+				if c.contains(&!lit) {
+					tautology = true;
+				}else if !c.contains(&lit) {
+					c.push(lit);
+				}
+			}
+			if c.is_empty() {
+				unsat = true;
+			}else if !tautology {
+				lits.push(c);
+			}
+		}
+		let mut occurs = vec![Vec::<usize>::new(); num_var * 2];
+		for (i, clause) in lits.iter().enumerate() {
+			for lit in clause {
+				occurs[lit.index()].push(i);
+			}
+		}
+		ModelCounter {
+			clauses: 	lits,
+			occurs,
+			num_var,
+			projected: 	vec![true; num_var],
+			value: 		vec![VUndef; num_var],
+			trail: 		Vec::<usize>::new(),
+			parent: 	vec![usize::MAX; num_var],
+			component: 	vec![usize::MAX; num_var],
+			score: 		vec![0; num_var],
+			cache: 		HashMap::<Vec<u32>, BigNum>::new(),
+			cache_len: 	0,
+			unsat,
+		}
+	}
+	
+	///Count the models on the given variables only
+	pub fn set_projection(&mut self, vars: &[Var]) {
+		self.projected = vec![false; self.num_var];
+		for var in vars {
+			if var.get_num() < self.num_var {
+				self.projected[var.get_num()] = true;
+			}
+		}
+		self.cache.clear();
+		self.cache_len = 0;
+	}
+	
+	///Count the models
+	pub fn count(&mut self) -> BigNum {
+		if self.unsat {
+			return BigNum::zero();
+		}
+		let start = self.trail.len();
+		let mut res = BigNum::zero();
+		let mut conflict = false;
+		for i in 0..self.clauses.len() {
+			if self.clauses[i].len() == 1 {
+				let lit = self.clauses[i][0];
+				match self.lit_value(lit) {
+					VUndef => self.assign(lit),
+					VFalse => conflict = true,
+					VTrue => {},
+				}
+			}
+		}
+		if !conflict {
+			let ids: Vec<usize> = (0..self.clauses.len()).collect();
+			let vars: Vec<usize> = (0..self.num_var).collect();
+			res = self.count_formula(&ids, &vars, start);
+		}
+		self.undo(start);
+		res
+	}
+	
+	///Return the number of components whose counts are cached
+	pub fn cache_size(&self) -> usize {
+		self.cache.len()
+	}
+	
+	//count the models of the clauses over the variables, after propagating the assignments
+	//from the given position of the trail
+	//the branches are followed with a stack instead of recursion, as they can be as deep as the variables
+	fn count_formula(&mut self, ids: &[usize], vars: &[usize], start: usize) -> BigNum {
+		let mut stack = vec![self.split_formula(ids, vars, start)];
+		//count of the frame popped last, returned to the one below it
+		let mut ret: Option<BigNum> = None;
+		while let Some(frame) = stack.pop() {
+			match frame {
+				Frame::Formula(mut formula) => {
+					if let Some(count) = ret.take() {
+						formula.res = &formula.res * &count;
+					}
+					let (ids, mut vars) = match formula.components.pop() {
+						Some(component) if !formula.res.is_zero() => component,
+						_ => {
+							ret = Some(formula.res);
+							continue;
+						},
+					};
+					stack.push(Frame::Formula(formula));
+					vars.sort();
+					
+					//the remaining clauses are determined by their unassigned variables
+					let mut key: Vec<u32> = vars.iter().map(|&v| v as u32).collect();
+					key.push(u32::MAX);
+					key.extend(ids.iter().map(|&c| c as u32));
+					if let Some(res) = self.cache.get(&key) {
+						ret = Some(res.clone());
+						continue;
+					}
+					let var = self.branch_var(&ids, &vars);
+					let start = self.trail.len();
+					self.assign(Lit::new(Var::new(var)));
+					let branch = self.split_formula(&ids, &vars, start);
+					stack.push(Frame::Component(ComponentFrame {
+						key,
+						ids,
+						vars,
+						var,
+						start,
+						first: 	true,
+						res: 	BigNum::zero(),
+					}));
+					stack.push(branch);
+				},
+				Frame::Component(mut component) => {
+					let count = ret.take().unwrap();
+					self.undo(component.start);
+					let projected = self.projected[component.var];
+					if projected {
+						component.res = &component.res + &count;
+					}else if !count.is_zero() {
+						//only if the component is satisfiable matters when no counted variable is left
+						component.res = BigNum::one();
+						component.first = false;
+					}
+					if component.first {
+						//count the branch of the negative literal
+						component.first = false;
+						self.assign(!Lit::new(Var::new(component.var)));
+						let branch = self.split_formula(&component.ids, &component.vars, component.start);
+						stack.push(Frame::Component(component));
+						stack.push(branch);
+						continue;
+					}
+					if self.cache_len + component.key.len() > CACHE_LIMIT {
+						self.cache.clear();
+						self.cache_len = 0;
+					}
+					self.cache_len += component.key.len();
+					self.cache.insert(component.key, component.res.clone());
+					ret = Some(component.res);
+				},
+			}
+		}
+		ret.unwrap()
+	}
+	
+	//propagate the assignments from the given position of the trail, and split the remaining clauses
+	//into components which share no variable
+	fn split_formula(&mut self, ids: &[usize], vars: &[usize], start: usize) -> Frame {
+		if !self.propagate(start) {
+			return Frame::Formula(FormulaFrame {
+				res: 		BigNum::zero(),
+				components: Vec::new(),
+			});
+		}
+		let rest: Vec<usize> = ids.iter().cloned().filter(|&c| !self.satisfied(c)).collect();
+		
+		//union the variables of each clause
+		let mut used = Vec::<usize>::new();
+		for &c in &rest {
+			let mut first = usize::MAX;
+			for i in 0..self.clauses[c].len() {
+				let v = self.clauses[c][i].var_num();
+				if self.value[v] != VUndef {
+					continue;
+				}
+				if self.parent[v] == usize::MAX {
+					self.parent[v] = v;
+					used.push(v);
+				}
+				if first == usize::MAX {
+					first = self.find(v);
+				}else {
+					let root = self.find(v);
+					self.parent[root] = first;
+				}
+			}
+		}
+		
+		//the variables in no clause can take any value
+		let free = vars.iter().filter(|&&v| self.value[v] == VUndef && self.parent[v] == usize::MAX && self.projected[v]).count();
+		
+		//split the clauses into components which share no variable
+		let mut components = Vec::<(Vec<usize>, Vec<usize>)>::new();
+		for &v in &used {
+			let root = self.find(v);
+			if self.component[root] == usize::MAX {
+				self.component[root] = components.len();
+				components.push((Vec::<usize>::new(), Vec::<usize>::new()));
+			}
+			components[self.component[root]].1.push(v);
+		}
+		for &c in &rest {
+			let v = self.clauses[c].iter().find(|l| self.value[l.var_num()] == VUndef).unwrap().var_num();
+			let root = self.find(v);
+			components[self.component[root]].0.push(c);
+		}
+		for &v in &used {
+			self.parent[v] = usize::MAX;
+			self.component[v] = usize::MAX;
+		}
+		
+		//the components are popped from the back, so they are counted in their order
+		components.reverse();
+		Frame::Formula(FormulaFrame {
+			res: 	BigNum::one().shl(free),
+			components,
+		})
+	}
+	
+	//pick the variable a component branches on, the counted variable with the highest score first
+	fn branch_var(&mut self, ids: &[usize], vars: &[usize]) -> usize {
+		//score the variables by their clauses, the shorter the clause the higher
+		for &c in ids {
+			let len = self.clauses[c].iter().filter(|l| self.value[l.var_num()] == VUndef).count();
+			let weight = 1 << (16 - len.min(16));
+			for i in 0..self.clauses[c].len() {
+				let v = self.clauses[c][i].var_num();
+				if self.value[v] == VUndef {
+					self.score[v] += weight;
+				}
+			}
+		}
+		let var = *vars.iter().max_by_key(|&&v| (self.projected[v], self.score[v], usize::MAX - v)).unwrap();
+		for &v in vars {
+			self.score[v] = 0;
+		}
+		var
+	}
+	
+	//assign the unit clauses until fixpoint, starting from the given position of the trail
+	//return false if a clause is falsified
+	fn propagate(&mut self, start: usize) -> bool {
+		let mut head = start;
+		while head < self.trail.len() {
+			let var = self.trail[head];
+			head += 1;
+			//the literal of the variable which became false
+			let false_lit = var * 2 + if self.value[var] == VTrue {1} else {0};
+			for i in 0..self.occurs[false_lit].len() {
+				let c = self.occurs[false_lit][i];
+				let mut unit = None;
+				let mut num_undef = 0;
+				let mut satisfied = false;
+				for &lit in &self.clauses[c] {
+					match self.lit_value(lit) {
+						VTrue => {
+							satisfied = true;
+							break;
+						},
+						VUndef => {
+							unit = Some(lit);
+							num_undef += 1;
+						},
+						VFalse => {},
+					}
+				}
+				if satisfied || num_undef > 1 {
+					continue;
+				}
+				match unit {
+					None => return false,
+					Some(lit) => self.assign(lit),
+				}
+			}
+		}
+		true
+	}
+	
+	fn satisfied(&self, c: usize) -> bool {
+		self.clauses[c].iter().any(|&l| self.lit_value(l) == VTrue)
+	}
+	
+	fn find(&mut self, v: usize) -> usize {
+		let mut root = v;
+		while self.parent[root] != root {
+			root = self.parent[root];
+		}
+		let mut v = v;
+		while self.parent[v] != root {
+			let next = self.parent[v];
+			self.parent[v] = root;
+			v = next;
+		}
+		root
+	}
+	
+	fn lit_value(&self, lit: Lit) -> VarValue {
+		let value = self.value[lit.var_num()];
+		if value == VUndef {
+			VUndef
+		}else if value == lit.get_value() {
+			VTrue
+		}else {
+			VFalse
+		}
+	}
+	
+	fn assign(&mut self, lit: Lit) {
+		self.value[lit.var_num()] = lit.get_value();
+		self.trail.push(lit.var_num());
+	}
+	
+	//undo the assignments after the given position of the trail
+	fn undo(&mut self, start: usize) {
+		for &var in &self.trail[start..] {
+			self.value[var] = VUndef;
+		}
+		self.trail.truncate(start);
+	}
+}
@@ -0,0 +1,63 @@
+use std::io::{BufRead, Write};
+use sat_lib::{Clause, Lit};
+
+///Read the clauses of a CNF in DIMACS format. Comment lines and the `p cnf` header are skipped,
+///and a clause may span several lines.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///let text = "c example\np cnf 3 2\n1 -3 0\n2 3 -1 0\n";
+///let clauses = read_dimacs(text.as_bytes()).unwrap();
+///
+///println!("{}", clauses[1]); //(1\/2\/~0)
+///```
+pub fn read_dimacs<R: BufRead>(reader: R) -> Result<Vec<Clause>, String> {
+	let mut clauses = Vec::<Clause>::new();
+	let mut clause = Clause::new();
+	for line in reader.lines() {
+		let line = line.map_err(|e| e.to_string())?;
+		let line = line.trim();
+		if line.starts_with('c') || line.starts_with('p') || line.starts_with('%') {
+			continue;
+		}
+		for token in line.split_whitespace() {
+			let num = token.parse::<i64>().map_err(|_| format!("Invalid literal: {}", token))?;
+			match Lit::parse_dimacs(num)? {
+				Some(lit) => clause.push(lit),
+				None => clauses.push(std::mem::take(&mut clause)),
+			}
+		}
+	}
+	if !clause.is_empty() {
+		return Err("The last clause is not terminated by 0".to_string());
+	}
+	Ok(clauses)
+}
+
+///Write clauses as a CNF in DIMACS format, with a `p cnf` header.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///let mut solver = Solver::new();
+///let x = Lit::create_lits(&solver.create_vars(3));
+///solver.add_clause_from_lits(vec![x[0], !x[2]]).unwrap();
+///
+///let mut out = Vec::<u8>::new();
+///write_dimacs(&mut out, &solver.get_oringin_clauses()).unwrap();
+///println!("{}", String::from_utf8(out).unwrap()); //p cnf 3 1
+///```
+pub fn write_dimacs<W: Write>(mut writer: W, clauses: &[Clause]) -> Result<(), String> {
+	let num_var = clauses.iter().filter_map(|c| c.get_max()).max().map_or(0, |v| v + 1);
+	writeln!(writer, "p cnf {} {}", num_var, clauses.len()).map_err(|e| e.to_string())?;
+	for clause in clauses {
+		for lit in &clause.vec_lit {
+			write!(writer, "{} ", lit.to_dimacs()).map_err(|e| e.to_string())?;
+		}
+		writeln!(writer, "0").map_err(|e| e.to_string())?;
+	}
+	Ok(())
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+use std::io::Write;
+use sat_lib::Lit;
+
+#[derive (Debug, Copy, Clone, PartialEq, Eq)]
+///Encoding of a DRAT proof.
+pub enum ProofFormat {
+	///One clause per line, such as `1 -2 0` for an added clause and `d 1 -2 0` for a deleted one.
+	Text,
+	///The compact binary encoding read by drat-trim, where `a` or `d` is followed by
+	///variable-length encoded literals and a zero byte.
+	Binary,
+}
+
+//Writes the clauses added and deleted by the solver into a DRAT proof.
+//The first failed write is kept, and nothing is written after it.
+pub struct ProofWriter {
+	sink: 	Box<dyn Write + Send>,
+	format: ProofFormat,
+	buf: 	Vec<u8>,			//encoding of the current line
+	error: 	Option<String>,		//the first error of the sink
+}
+
+impl fmt::Debug for ProofWriter {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "ProofWriter({:?})", self.format)
+	}
+}
+
+impl ProofWriter {
+	pub fn new(sink: Box<dyn Write + Send>, format: ProofFormat) -> Self {
+		ProofWriter {
+			sink,
+			format,
+			buf: 	Vec::<u8>::new(),
+			error: 	None,
+		}
+	}
+	
+	//add a clause which is RUP or RAT to the current clauses
+	pub fn add(&mut self, lits: &[Lit]) {
+		self.write(false, lits);
+	}
+	
+	//delete a clause
+	pub fn delete(&mut self, lits: &[Lit]) {
+		self.write(true, lits);
+	}
+	
+	//flush the sink and return the first error
+	pub fn finish(mut self) -> Result<(), String> {
+		if self.error.is_none() {
+			if let Err(e) = self.sink.flush() {
+				self.error = Some(e.to_string());
+			}
+		}
+		match self.error {
+			Some(e) => Err(e),
+			None => Ok(()),
+		}
+	}
+	
+	fn write(&mut self, delete: bool, lits: &[Lit]) {
+		if self.error.is_some() {
+			return;
+		}
+		self.buf.clear();
+		match self.format {
+			ProofFormat::Text => {
+				if delete {
+					self.buf.extend_from_slice(b"d ");
+				}
+				for lit in lits {
+					self.buf.extend_from_slice(format!("{} ", lit.to_dimacs()).as_bytes());
+				}
+				self.buf.extend_from_slice(b"0\n");
+			},
+			ProofFormat::Binary => {
+				self.buf.push(if delete {b'd'} else {b'a'});
+				for lit in lits {
+					//2 * var + sign, with variables counted from 1
+					let num = lit.to_dimacs();
+					let mut num = num.unsigned_abs() * 2 + if num < 0 {1} else {0};
+					while num > 0x7f {
+						self.buf.push((num & 0x7f) as u8 | 0x80);
+						num >>= 7;
+					}
+					self.buf.push(num as u8);
+				}
+				self.buf.push(0);
+			},
+		}
+		if let Err(e) = self.sink.write_all(&self.buf) {
+			self.error = Some(e.to_string());
+		}
+	}
+}
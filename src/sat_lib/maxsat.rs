@@ -0,0 +1,405 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, Write};
+use sat_lib::{Solver, SolverConfig, SolveResult, Clause, Lit, Var, VarValue};
+
+#[derive (Debug, Copy, Clone, PartialEq, Eq)]
+///Algorithm used by MaxSatSolver::solve
+pub enum MaxSatAlgorithm {
+	///Find a model, then ask for a model of lower cost until there is none (SAT-UNSAT search).
+	///The weight of the falsified soft clauses is bounded by a generalized totalizer.
+	LinearSearch,
+	///Relax the soft clauses in each UNSAT core by a totalizer until they are satisfiable (OLL),
+	///starting from the soft clauses of the highest weight (stratification).
+	CoreGuided,
+}
+
+#[derive (Debug, Clone)]
+///Weighted partial MaxSAT solver. Hard clauses must be satisfied, and the sum of the weights of
+///the falsified soft clauses, called the cost, is minimized.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///let mut maxsat = MaxSatSolver::new();
+///let x = Lit::create_lits(&maxsat.create_vars(2));
+///let mut hard = Clause::new();
+///hard.push(!x[0]);
+///hard.push(!x[1]);
+///maxsat.add_clause(hard);
+///
+///let mut soft = Clause::new();
+///soft.push(x[0]);
+///maxsat.add_soft_clause(soft.clone(), 3);
+///soft = Clause::new();
+///soft.push(x[1]);
+///maxsat.add_soft_clause(soft, 5);
+///
+///println!("{}", maxsat.solve(MaxSatAlgorithm::CoreGuided).unwrap()); //SAT
+///println!("{:?}", maxsat.get_cost()); //Some(3)
+///println!("{}", maxsat.get_model()[1]); //T
+///```
+pub struct MaxSatSolver {
+	hard: 		Vec<Clause>,
+	soft: 		Vec<(Clause, u64)>,		//soft clauses with their weights
+	num_var: 	usize,
+	config: 	SolverConfig,			//configuration of the SAT solvers used
+	model: 		Vec<VarValue>,			//optimal model found by the last solve
+	cost: 		Option<u64>,			//cost of the model
+}
+
+impl Default for MaxSatSolver {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MaxSatSolver {
+	///Create a new MaxSAT solver
+	pub fn new() -> Self {
+		MaxSatSolver::with_config(SolverConfig::default())
+	}
+	
+	///Create a new MaxSAT solver whose SAT solvers use the given configuration
+	pub fn with_config(config: SolverConfig) -> Self {
+		MaxSatSolver {
+			hard: 		Vec::<Clause>::new(),
+			soft: 		Vec::<(Clause, u64)>::new(),
+			num_var: 	0,
+			config,
+			model: 		Vec::<VarValue>::new(),
+			cost: 		None,
+		}
+	}
+	
+	///Create a new variable
+	pub fn new_var(&mut self) -> Var {
+		self.num_var += 1;
+		Var::new(self.num_var - 1)
+	}
+	
+	///Create the given number of new variables
+	pub fn create_vars(&mut self, num: usize) -> Vec<Var> {
+		(0..num).map(|_| self.new_var()).collect()
+	}
+	
+	///Add a hard clause, which every model satisfies
+	pub fn add_clause(&mut self, clause: Clause) {
+		if let Some(var_num) = clause.get_max() {
+			self.num_var = self.num_var.max(var_num + 1);
+		}
+		self.hard.push(clause);
+	}
+	
+	///Add a soft clause, whose weight is added to the cost of the models falsifying it
+	pub fn add_soft_clause(&mut self, clause: Clause, weight: u64) {
+		if let Some(var_num) = clause.get_max() {
+			self.num_var = self.num_var.max(var_num + 1);
+		}
+		self.soft.push((clause, weight));
+	}
+	
+	///Return the hard clauses
+	pub fn get_hard_clauses(&self) -> &[Clause] {
+		&self.hard
+	}
+	
+	///Return the soft clauses with their weights
+	pub fn get_soft_clauses(&self) -> &[(Clause, u64)] {
+		&self.soft
+	}
+	
+	///Return the model found by the last solve, which has the minimum cost
+	pub fn get_model(&self) -> &[VarValue] {
+		&self.model
+	}
+	
+	///Return the cost of the model found by the last solve, or None if there is no model
+	pub fn get_cost(&self) -> Option<u64> {
+		self.cost
+	}
+	
+	///Return the sum of the weights of the soft clauses falsified by the model, or None if it
+	///does not fit in u64
+	pub fn cost_of(&self, model: &[VarValue]) -> Option<u64> {
+		self.soft.iter().filter(|&(c, _)| !satisfies(model, c)).try_fold(0u64, |sum, &(_, w)| sum.checked_add(w))
+	}
+	
+	///Find a model of the hard clauses of the minimum cost. Return SAT if it is found, UNSAT if the
+	///hard clauses are UNSAT, or the result of the SAT solver if it stops early.
+	///
+	///# Error
+	///
+	///This function will return error if the sum of the weights of the soft clauses does not fit in u64.
+	pub fn solve(&mut self, algorithm: MaxSatAlgorithm) -> Result<SolveResult, String> {
+		self.model.clear();
+		self.cost = None;
+		//every cost and every sum of the totalizers is at most the total weight
+		if self.soft.iter().try_fold(0u64, |sum, &(_, w)| sum.checked_add(w)).is_none() {
+			return Err("The sum of the weights of the soft clauses overflows".to_string());
+		}
+		let mut solver = Solver::with_config(self.config.clone());
+		solver.create_vars(self.num_var);
+		for clause in &self.hard {
+			solver.add_clause(clause.clone()).unwrap_or(false);
+		}
+		Ok(match algorithm {
+			MaxSatAlgorithm::LinearSearch => self.linear_search(solver),
+			MaxSatAlgorithm::CoreGuided => self.core_guided(solver),
+		})
+	}
+	
+	//SAT-UNSAT search: relax every soft clause by a new variable, and bound the weights of the relaxed
+	//clauses below the cost of the last model
+	fn linear_search(&mut self, mut solver: Solver) -> SolveResult {
+		let mut relax = Vec::<(Lit, u64)>::new();
+		for (clause, weight) in &self.soft {
+			if *weight > 0 {
+				let lit = Lit::new(solver.new_var());
+				let mut lits = clause.get_all_lits();
+				lits.push(lit);
+				solver.add_clause_from_lits(lits).unwrap_or(false);
+				relax.push((lit, *weight));
+			}
+		}
+		let mut sums: Option<BTreeMap<u64, Lit>> = None;
+		loop {
+			match solver.solve() {
+				SolveResult::Sat => {
+					let model = solver.get_model()[..self.num_var].to_vec();
+					let cost = self.cost_of(&model).unwrap();
+					self.model = model;
+					self.cost = Some(cost);
+					if cost == 0 {
+						return SolveResult::Sat;
+					}
+					//the sums over the first cost are not needed, so they are merged
+					let sums = sums.get_or_insert_with(|| totalizer(&mut solver, &relax, cost));
+					for (_, &lit) in sums.range(cost..) {
+						solver.add_clause_from_lits(vec![!lit]).unwrap_or(false);
+					}
+				},
+				SolveResult::Unsat if self.cost.is_some() => return SolveResult::Sat,
+				result => return result,
+			}
+		}
+	}
+	
+	//OLL with stratification: each soft clause is enabled by assuming its selector. The minimum weight
+	//of the assumptions in an UNSAT core is paid, and moved to a new assumption that at most one of
+	//them is false, given by a totalizer over the core. When the assumption that at most k of them are
+	//false is in a core, the one for k + 1 is added
+	fn core_guided(&mut self, mut solver: Solver) -> SolveResult {
+		//assumption and weight of each working soft constraint
+		let mut soft = Vec::<(Lit, u64)>::new();
+		//totalizer and bound of the assumptions which are not false for more than bound inputs
+		let mut bounds = HashMap::<Lit, (usize, usize)>::new();
+		let mut totalizers = Vec::<BTreeMap<u64, Lit>>::new();
+		for (clause, weight) in &self.soft {
+			if *weight > 0 {
+				let selector = Lit::new(solver.new_var());
+				let mut lits = clause.get_all_lits();
+				lits.push(!selector);
+				solver.add_clause_from_lits(lits).unwrap_or(false);
+				soft.push((selector, *weight));
+			}
+		}
+		let mut threshold = soft.iter().map(|s| s.1).max().unwrap_or(0);
+		loop {
+			let assumptions: Vec<Lit> = soft.iter().filter(|s| s.1 > 0 && s.1 >= threshold).map(|s| s.0).collect();
+			match solver.solve_with_assumptions(&assumptions) {
+				SolveResult::Sat => {
+					match soft.iter().map(|s| s.1).filter(|&w| w > 0 && w < threshold).max() {
+						Some(weight) => threshold = weight,
+						None => {
+							self.model = solver.get_model()[..self.num_var].to_vec();
+							self.cost = self.cost_of(&self.model);
+							return SolveResult::Sat;
+						},
+					}
+				},
+				SolveResult::Unsat => {
+					let mut core: Vec<usize> = (0..soft.len()).filter(|&i| soft[i].1 > 0 && soft[i].1 >= threshold && solver.is_failed(soft[i].0)).collect();
+					//the hard clauses are UNSAT by themselves
+					if core.is_empty() {
+						return SolveResult::Unsat;
+					}
+					//shrink the core by solving under it until it does not change
+					loop {
+						let assumptions: Vec<Lit> = core.iter().map(|&i| soft[i].0).collect();
+						if solver.solve_with_assumptions(&assumptions) != SolveResult::Unsat {
+							break;
+						}
+						let trimmed: Vec<usize> = core.iter().cloned().filter(|&i| solver.is_failed(soft[i].0)).collect();
+						if trimmed.len() == core.len() {
+							break;
+						}
+						core = trimmed;
+					}
+					let min = core.iter().map(|&i| soft[i].1).min().unwrap();
+					for &i in &core {
+						soft[i].1 -= min;
+						let lit = soft[i].0;
+						if core.len() == 1 {
+							solver.add_clause_from_lits(vec![!lit]).unwrap_or(false);
+						}
+						//the bound of a totalizer is increased
+						if let Some(&(t, bound)) = bounds.get(&lit) {
+							if let Some(&output) = totalizers[t].get(&(bound as u64 + 2)) {
+								bounds.insert(!output, (t, bound + 1));
+								soft.push((!output, min));
+							}
+						}
+					}
+					if core.len() > 1 {
+						let inputs: Vec<(Lit, u64)> = core.iter().map(|&i| (!soft[i].0, 1)).collect();
+						let outputs = totalizer(&mut solver, &inputs, inputs.len() as u64);
+						bounds.insert(!outputs[&2], (totalizers.len(), 1));
+						soft.push((!outputs[&2], min));
+						totalizers.push(outputs);
+					}
+				},
+				result => return result,
+			}
+		}
+	}
+	
+	///Read a MaxSAT instance in WCNF format. Both the format with a `p wcnf` header, where the
+	///clauses whose weight is at least top are hard, and the format marking hard clauses by `h` are read.
+	///
+	///# Error
+	///
+	///This function will return error if the text is not in WCNF format, or the sum of the weights of
+	///the soft clauses does not fit in u64.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let text = "p wcnf 2 3 10\n10 -1 -2 0\n3 1 0\n5 2 0\n";
+	///let mut maxsat = MaxSatSolver::read_wcnf(text.as_bytes()).unwrap();
+	///
+	///println!("{}", maxsat.solve(MaxSatAlgorithm::LinearSearch).unwrap()); //SAT
+	///println!("{:?}", maxsat.get_cost()); //Some(3)
+	///```
+	pub fn read_wcnf<R: BufRead>(reader: R) -> Result<Self, String> {
+		let mut maxsat = MaxSatSolver::new();
+		let mut top = None;
+		let mut total = 0u64;
+		for line in reader.lines() {
+			let line = line.map_err(|e| e.to_string())?;
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('c') {
+				continue;
+			}
+			let mut tokens = line.split_whitespace();
+			let first = tokens.next().unwrap();
+			if first == "p" {
+				if tokens.next() != Some("wcnf") {
+					return Err(format!("Invalid header: {}", line));
+				}
+				if let Some(token) = tokens.nth(2) {
+					top = Some(token.parse::<u64>().map_err(|_| format!("Invalid top weight: {}", token))?);
+				}
+				continue;
+			}
+			let weight = if first == "h" {
+				None
+			}else {
+				let weight = first.parse::<u64>().map_err(|_| format!("Invalid weight: {}", first))?;
+				if top.is_some_and(|top| weight >= top) {None} else {Some(weight)}
+			};
+			if let Some(weight) = weight {
+				total = total.checked_add(weight).ok_or_else(|| format!("The sum of the weights overflows: {}", line))?;
+			}
+			let mut clause = Clause::new();
+			let mut terminated = false;
+			for token in tokens {
+				let num = token.parse::<i64>().map_err(|_| format!("Invalid literal: {}", token))?;
+				match Lit::parse_dimacs(num)? {
+					Some(lit) => clause.push(lit),
+					None => {
+						terminated = true;
+						break;
+					},
+				}
+			}
+			if !terminated {
+				return Err(format!("The clause is not terminated by 0: {}", line));
+			}
+			match weight {
+				Some(weight) => maxsat.add_soft_clause(clause, weight),
+				None => maxsat.add_clause(clause),
+			}
+		}
+		Ok(maxsat)
+	}
+	
+	///Write the instance in WCNF format, with a `p wcnf` header whose top weight is greater than
+	///the sum of the weights of the soft clauses.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let mut maxsat = MaxSatSolver::new();
+	///let x = Lit::create_lits(&maxsat.create_vars(2));
+	///let mut soft = Clause::new();
+	///soft.push(!x[1]);
+	///maxsat.add_soft_clause(soft, 4);
+	///
+	///let mut out = Vec::<u8>::new();
+	///maxsat.write_wcnf(&mut out).unwrap();
+	///println!("{}", String::from_utf8(out).unwrap()); //p wcnf 2 1 5
+	///```
+	pub fn write_wcnf<W: Write>(&self, mut writer: W) -> Result<(), String> {
+		let top = self.soft.iter().fold(1u64, |sum, &(_, w)| sum.saturating_add(w));
+		writeln!(writer, "p wcnf {} {} {}", self.num_var, self.hard.len() + self.soft.len(), top).map_err(|e| e.to_string())?;
+		let hard = self.hard.iter().map(|c| (c, top));
+		let soft = self.soft.iter().map(|&(ref c, w)| (c, w));
+		for (clause, weight) in hard.chain(soft) {
+			write!(writer, "{} ", weight).map_err(|e| e.to_string())?;
+			for lit in &clause.vec_lit {
+				write!(writer, "{} ", lit.to_dimacs()).map_err(|e| e.to_string())?;
+			}
+			writeln!(writer, "0").map_err(|e| e.to_string())?;
+		}
+		Ok(())
+	}
+}
+
+//check if the model satisfies the clause
+fn satisfies(model: &[VarValue], clause: &Clause) -> bool {
+	clause.vec_lit.iter().any(|l| model.get(l.var_num()) == Some(&l.get_value()))
+}
+
+//generalized totalizer: return a literal for each possible sum of the weights of the true inputs,
+//which is implied when the sum is at least that value. The sums over cap are merged into cap, so
+//they saturate instead of overflowing
+fn totalizer(solver: &mut Solver, inputs: &[(Lit, u64)], cap: u64) -> BTreeMap<u64, Lit> {
+	let mut sums = BTreeMap::<u64, Lit>::new();
+	if inputs.len() == 1 {
+		sums.insert(inputs[0].1.min(cap), inputs[0].0);
+	}else if inputs.len() > 1 {
+		let (left, right) = inputs.split_at(inputs.len() / 2);
+		let left = totalizer(solver, left, cap);
+		let right = totalizer(solver, right, cap);
+		for &a in left.keys().chain(right.keys()) {
+			sums.entry(a).or_insert_with(|| Lit::new(solver.new_var()));
+		}
+		for &a in left.keys() {
+			for &b in right.keys() {
+				sums.entry(a.saturating_add(b).min(cap)).or_insert_with(|| Lit::new(solver.new_var()));
+			}
+		}
+		for (a, &x) in left.iter().chain(right.iter()) {
+			solver.add_clause_from_lits(vec![!x, sums[a]]).unwrap_or(false);
+		}
+		for (&a, &x) in &left {
+			for (&b, &y) in &right {
+				solver.add_clause_from_lits(vec![!x, !y, sums[&a.saturating_add(b).min(cap)]]).unwrap_or(false);
+			}
+		}
+	}
+	sums
+}
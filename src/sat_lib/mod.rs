@@ -1,9 +1,48 @@
 use sat_lib::VarValue::*;
 
 use std::fmt;
-use std::collections::HashSet;
 use std::ops::Not;
-use std::cmp::Ordering;
+use std::mem;
+use std::cmp::Reverse;
+use std::io::Write;
+use std::time::Instant;
+use std::sync::Arc;
+use std::sync::atomic::{self, AtomicBool};
+
+use sat_lib::order::VarOrder;
+use sat_lib::arena::{Cnf, CRef};
+use sat_lib::restart::RestartPolicy;
+pub use sat_lib::config::*;
+pub use sat_lib::stats::*;
+pub use sat_lib::observer::*;
+pub use sat_lib::drat::ProofFormat;
+use sat_lib::drat::ProofWriter;
+pub use sat_lib::dimacs::*;
+pub use sat_lib::proof::*;
+pub use sat_lib::models::Models;
+pub use sat_lib::bignum::*;
+pub use sat_lib::count::*;
+pub use sat_lib::maxsat::*;
+pub use sat_lib::sls::*;
+
+mod order;
+mod arena;
+mod restart;
+mod config;
+mod stats;
+mod observer;
+mod drat;
+mod dimacs;
+mod proof;
+mod models;
+mod bignum;
+mod count;
+mod maxsat;
+mod sls;
+mod simp;
+
+//largest index of a variable, as a literal packs its variable and its sign into 32 bits
+const MAX_VAR: usize = (u32::MAX >> 1) as usize;
 
 #[derive (Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 ///Used to represent variable used in CNF.
@@ -15,17 +54,23 @@ pub struct Var {
 impl Var {
 	///Create a new variable using given index number.
 	///
+	///# Panics
+	///
+	///Panics if the index number is greater than 2^31 - 1.
+	///
 	///# Examples
 	///
 	/// ```
+	///# use sat::sat_lib::*;
 	///let v = Var::new(4);
 	///
 	///println!("{}", v); //4
 	/// ```
 	///
 	pub fn new(num: usize) -> Self {
+		assert!(num <= MAX_VAR, "Variable out of range: {}", num);
 		Var {
-			num: num,
+			num,
 		}
 	}
 	
@@ -34,9 +79,10 @@ impl Var {
 	///# Examples
 	///
 	/// ```
+	///# use sat::sat_lib::*;
 	///let v = Var::new(5);
 	///
-	///println!("{}", v.var_num); //5
+	///println!("{}", v.get_num()); //5
 	/// ```
 	///
 	pub fn get_num(&self) -> usize {
@@ -56,7 +102,7 @@ impl fmt::Display for Var {
 ///# Examples
 ///
 ///```
-///
+///# use sat::sat_lib::VarValue::*;
 ///println!("{}", VTrue);  //T
 ///println!("{}", VFalse); //F
 ///println!("{}", VUndef); //X
@@ -76,6 +122,7 @@ impl VarValue {
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::VarValue::*;
 	///let t = VTrue;
 	///
 	///println!("{}", t.equals(VTrue));  //true
@@ -122,13 +169,12 @@ impl fmt::Display for VarValue {
 	} 
 }
 
-#[derive (Debug, Copy, Clone, PartialEq, Eq, Hash)]
-///Used to represent literals in CNF
+#[derive (Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///Used to represent literals in CNF. Literals are ordered by their variables, and the positive
+///literal of a variable comes first.
 pub struct Lit {
-	///Corresponding variable
-	var: 	Var,
-	///The value of literal
-	value: 	VarValue,
+	///2 * variable, plus 1 if the literal is negative
+	code: 	u32,
 }
 
 impl Lit {
@@ -137,39 +183,42 @@ impl Lit {
 	///# Examples
 	///
 	///```
-	///
+	///# use sat::sat_lib::*;
 	///let v = Var::new(5);
 	///let x0 = Lit::new(v);
 	///
 	///println!("x0 = {}, !x0 = {}", x0, !x0); //x0 = 5, !x0 = ~5
 	///```
 	pub fn new(var: Var) -> Self {
-		Lit {
-			var: 	var,
-			value: 	VTrue,
-		}
+		Lit::create(var.get_num(), true)
 	}
 	
 	fn create(var_num: usize, val: bool) -> Self {
+		assert!(var_num <= MAX_VAR, "Variable out of range: {}", var_num);
 		Lit {
-			var: 	Var::new(var_num),
-			value: 	if val {VTrue} else {VFalse},
+			code: 	var_num as u32 * 2 + if val {0} else {1},
 		}
 	}
 	
+	//index of the literal in per-literal tables, the two literals of a variable are adjacent
+	fn index(&self) -> usize {
+		self.code as usize
+	}
+	
 	///Get the index number of the variable
 	///
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let v = Var::new(5);
 	///let x0 = Lit::new(v);
-	///let x0_num = x0.var_num();
+	///let v_num = x0.var_num();
 	///
 	///println!("v_num = {}", v_num); //v_num = 5
 	///```
 	pub fn var_num(&self) -> usize {
-		self.var.get_num()
+		(self.code >> 1) as usize
 	}
 	
 	///Get the value of the variable
@@ -177,13 +226,14 @@ impl Lit {
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let v = Var::new(5);
 	///let x0 = Lit::new(v);
 	///
 	///println!("x0 value = {}, !x0 value = {}", x0.get_value(), !x0.get_value()); // x0 = T, !x0 = F
 	///```
 	pub fn get_value(&self) -> VarValue {
-		self.value
+		if self.code & 1 == 0 {VTrue} else {VFalse}
 	}
 	
 	///Given a list of variables, create and return a list of responding literals
@@ -191,12 +241,13 @@ impl Lit {
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
 	///let vars = solver.create_vars(100);
-	///let x = create_lits(&vars);
+	///let x = Lit::create_lits(&vars);
 	///
 	///solver.add_clause_from_lits(vec![x[0], !x[2], x[80]]).unwrap();
-	///solver.add_clause_from_lits(vec![!x[57], x[24]).unwrap();
+	///solver.add_clause_from_lits(vec![!x[57], x[24]]).unwrap();
 	///
 	///println!("{}", solver); //(0\/~2\/80)/\(~57\/24)
 	///```
@@ -207,6 +258,51 @@ impl Lit {
 		}
 		lits
 	}
+	
+	///Get the literal in DIMACS format, where variables are counted from 1 and negative numbers are negated literals
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let x0 = Lit::new(Var::new(5));
+	///
+	///println!("{} {}", x0.to_dimacs(), (!x0).to_dimacs()); //6 -6
+	///```
+	pub fn to_dimacs(&self) -> i64 {
+		let num = self.var_num() as i64 + 1;
+		if self.code & 1 == 1 {-num} else {num}
+	}
+	
+	///Create a literal from DIMACS format. Return None for 0, which ends a clause in DIMACS.
+	///
+	///# Panics
+	///
+	///Panics if the absolute value is greater than 2^31, i.e. the variable is out of range.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let x0 = Lit::from_dimacs(-6).unwrap();
+	///
+	///println!("{}", x0); //~5
+	///```
+	pub fn from_dimacs(num: i64) -> Option<Self> {
+		if num == 0 {
+			None
+		}else {
+			Some(Lit::create(num.unsigned_abs() as usize - 1, num > 0))
+		}
+	}
+	
+	//from_dimacs for the parsers of files, which return an error for a variable out of range
+	fn parse_dimacs(num: i64) -> Result<Option<Self>, String> {
+		if num.unsigned_abs() > MAX_VAR as u64 + 1 {
+			return Err(format!("Invalid literal: {}", num));
+		}
+		Ok(Lit::from_dimacs(num))
+	}
 }
 
 impl Not for Lit {
@@ -214,30 +310,17 @@ impl Not for Lit {
 
 	fn not(self) -> Lit {
 		Lit {
-			var: 	self.var,
-			value: 	!self.value,
+			code: 	self.code ^ 1,
 		}
 	}
 }
 
-impl PartialOrd for Lit {
-	fn partial_cmp(&self, other: &Lit) -> Option<Ordering> {
-		Some(self.cmp(other))
-	}
-}
-
-impl Ord for Lit {
-	fn cmp(&self, other: &Lit) -> Ordering {
-		self.var.cmp(&other.var)
-	}
-}
-
 impl fmt::Display for Lit {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    	if self.value == VTrue {
-	        write!(f, "{}", self.var)
+    	if self.code & 1 == 0 {
+	        write!(f, "{}", self.var_num())
     	}else {
-	    	write!(f, "~{}", self.var)
+	    	write!(f, "~{}", self.var_num())
     	}
     }
 }
@@ -246,19 +329,23 @@ impl fmt::Display for Lit {
 ///Used to represent clauses
 pub struct Clause {
 	///Literals in the clause
-	vec_lit: 	Vec<(Lit, bool)>,
+	vec_lit: 	Vec<Lit>,
 	///The max index number of literals in the clause
 	max_var: 	Option<usize>,
-	///The number of literals, which are not logically removed, in the clause
-	len: 		usize,
+}
+
+impl Default for Clause {
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl Clause {
+	///Create a new empty clause
 	pub fn new() -> Self {
 		Clause {
-			vec_lit: 	Vec::<(Lit, bool)>::new(),
+			vec_lit: 	Vec::<Lit>::new(),
 			max_var: 	None,
-			len: 		0,
 		}
 	}
 	
@@ -267,12 +354,13 @@ impl Clause {
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let x0 = Lit::new(Var::new(0));
 	///let x1 = Lit::new(Var::new(1));
 	///
 	///let mut c = Clause::new();
-	///c.push(x[0]);
-	///c.push(!x[1]);
+	///c.push(x0);
+	///c.push(!x1);
 	///
 	///println!("{}", c); // (0\/~1)
 	///```
@@ -281,8 +369,7 @@ impl Clause {
 			Some(max_lit) => if max_lit < lit.var_num() {self.max_var = Some(lit.var_num());},
 			None => self.max_var = Some(lit.var_num()), 
 		};
-		self.vec_lit.push((lit, false));
-		self.len += 1;
+		self.vec_lit.push(lit);
 	}
 	
 	///get the number of literals in the clause
@@ -290,226 +377,256 @@ impl Clause {
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let x0 = Lit::new(Var::new(0));
 	///let x1 = Lit::new(Var::new(1));
 	///
 	///let mut c = Clause::new();
-	///c.push(x[0]);
-	///c.push(!x[1]);
+	///c.push(x0);
+	///c.push(!x1);
 	///
 	///println!("{}", c.len()); // 2
 	///```
 	pub fn len(&self) -> usize {
-		self.len
+		self.vec_lit.len()
+	}
+	
+	///check if there is no literal in the clause
+	pub fn is_empty(&self) -> bool {
+		self.vec_lit.is_empty()
 	}
 	
-	///return all lits, including those are marked
+	///return all lits in the clause
 	///
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let x0 = Lit::new(Var::new(0));
 	///let x1 = Lit::new(Var::new(1));
 	///
 	///let mut c = Clause::new();
-	///c.push(x[0]);
-	///c.push(!x[1]);
+	///c.push(x0);
+	///c.push(!x1);
 	///
 	///println!("{}", c.get_all_lits()[1]); //~1
 	///```
 	pub fn get_all_lits(&self) -> Vec<Lit> {
-		let mut v = Vec::<Lit>::new();
-		
-		for i in &self.vec_lit {
-			if !i.1 {
-				v.push(i.0);
-			}
-		}
-		
-		v
+		self.vec_lit.clone()
 	}
 	
-	//get first lit that is not marked
+	//get the first lit
 	fn get_first(&self) -> Option<Lit> {
-		if self.len > 0{
-			let mut i = 0;
-			while self.vec_lit[i].1 {
-				i += 1;
-			}
-			Some(self.vec_lit[i].0)
-		}else {
-			None
-		}
-	}
-	
-	//logically remove one lit
-	fn remove(&mut self, idx: usize) {
-		if !self.vec_lit[idx].1 {
-			self.vec_lit[idx].1 = true;
-			self.len -= 1;
-		}
-	}
-	
-	//restore the removed lit
-	fn restore(&mut self, idx: usize) {
-		if self.vec_lit[idx].1 {
-			self.vec_lit[idx].1 = false;
-			self.len += 1;
-		}
-	}
-	
-	//restore all lits, including not removed ones
-	fn restore_all(&mut self) {
-		self.len = self.vec_lit.len();
-		for i in 0..self.len {
-			self.vec_lit[i].1 = false;
-		}
+		self.vec_lit.first().cloned()
 	}
 	
 	//check if this clause is a valid clause, i.e. all lits are valid in the solver
 	fn get_max(&self) -> Option<usize> {
-		if let Some(var_num) = self.max_var {
-			Some(var_num)
-		}else {
-			None
-		}
+		self.max_var
 	}
 }
 
 impl fmt::Display for Clause {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "(").unwrap();
-		let mut first = true;
-			
-		for i in 0..self.vec_lit.len() {
-			if !self.vec_lit[i].1 {
-				if !first {
-					write!(f, "\\/").unwrap();
-				}
-				first = false;
-				write!(f, "{}", self.vec_lit[i].0).unwrap();
+		for (i, lit) in self.vec_lit.iter().enumerate() {
+			if i != 0 {
+				write!(f, "\\/").unwrap();
 			}
+			write!(f, "{}", lit).unwrap();
 		} 
 		write!(f, ")")
 	}
 }
 
-#[derive (Debug)]
-struct CNF {
-	clauses: 	Vec<Clause>,	//vector of clauses
-	sat: 		Vec<usize>,		//represent if the clause is satisfied or not. A clause is unsat implies vec_sat[ci] == 0
-}
-
-impl CNF {
-	fn new() -> Self {
-		CNF {
-			clauses: 	Vec::<Clause>::new(),
-			sat: 		Vec::<usize>::new(),		
-		}
-	}
-	
-	fn add_clause(&mut self, clause: Clause) {
-		self.clauses.push(clause);
-		self.sat.push(0);
-	}
-	
-	fn len(&self) -> usize {
-		self.clauses.len()
-	}
+#[derive (Debug, Copy, Clone)]
+//An entry in the watch list of a literal
+struct Watcher {
+	clause: 	CRef,	//reference of the watching clause
+	blocker: 	Lit,	//another literal of the clause, if it is true the clause need not be visited
 }
 
 #[derive (Debug)]
 struct Model {
 	var: 		Vec<VarValue>,				//the assignment of each variable
-	expected:	Vec<(VarValue, usize)>,		//expected value of variable during sovling process
-	map: 		VarMap,						//saves the lists of position of each variable appear in CNF
-	propagated: Vec<bool>,					//if the value of a variable is propagated through CNF
+	level: 		Vec<usize>,					//decision level at which each variable is assigned
+	reason: 	Vec<Option<CRef>>,			//clause which implies the assignment, None for decisions
+	trail: 		Vec<Lit>,					//assigned literals in chronological order
+	trail_lim: 	Vec<usize>,					//position in the trail where each decision level starts
+	qhead: 		usize,						//position in the trail of the next literal to propagate
+	seen: 		Vec<bool>,					//marks used by conflict analysis
+	watches: 	Vec<Vec<Watcher>>,			//clauses watching each literal, indexed by Lit::index
+	occurs: 	Vec<usize>,					//number of original clauses each literal appears in
+	phase: 		Vec<VarValue>,				//value of each variable when it was last unassigned
+	eliminated: Vec<bool>,					//if each variable is eliminated by preprocessing
+	witness: 	Vec<bool>,					//if each variable may be set when the model of preprocessing is rebuilt
+	level_stamp:Vec<usize>,					//marks of decision levels used to compute LBD
+	stamp: 		usize,
 }
 
 impl Model {
 	fn new() -> Self {
 		Model {
 			var: 		Vec::<VarValue>::new(),
-			expected:	Vec::<(VarValue, usize)>::new(),
-			map: 		VarMap::new(),
-			propagated: Vec::<bool>::new(),
+			level: 		Vec::<usize>::new(),
+			reason: 	Vec::<Option<CRef>>::new(),
+			trail: 		Vec::<Lit>::new(),
+			trail_lim: 	Vec::<usize>::new(),
+			qhead: 		0,
+			seen: 		Vec::<bool>::new(),
+			watches: 	Vec::<Vec<Watcher>>::new(),
+			occurs: 	Vec::<usize>::new(),
+			phase: 		Vec::<VarValue>::new(),
+			eliminated: Vec::<bool>::new(),
+			witness: 	Vec::<bool>::new(),
+			level_stamp:vec![0],
+			stamp: 		0,
 		}
 	}
 	
 	fn new_var(&mut self) {
 		self.var.push(VUndef);
-		self.expected.push((VUndef, 0));
-		self.propagated.push(false);
-		self.map.new_var();
+		self.level.push(0);
+		self.reason.push(None);
+		self.seen.push(false);
+		self.phase.push(VUndef);
+		self.eliminated.push(false);
+		self.witness.push(false);
+		self.level_stamp.push(0);
+		for _ in 0..2 {
+			self.watches.push(Vec::<Watcher>::new());
+			self.occurs.push(0);
+		}
+	}
+	
+	//remove the variable added last, which must not be assigned
+	fn remove_last_var(&mut self) {
+		self.var.pop();
+		self.level.pop();
+		self.reason.pop();
+		self.seen.pop();
+		self.phase.pop();
+		self.eliminated.pop();
+		self.witness.pop();
+		self.level_stamp.pop();
+		for _ in 0..2 {
+			self.watches.pop();
+			self.occurs.pop();
+		}
 	}
 	
-	fn len(&self) -> usize {
-		self.var.len()
+	//current decision level
+	fn decision_level(&self) -> usize {
+		self.trail_lim.len()
+	}
+	
+	//value of a literal under the current assignment
+	fn lit_value(&self, lit: Lit) -> VarValue {
+		match self.var[lit.var_num()] {
+			VUndef => VUndef,
+			value => if value == lit.get_value() {VTrue} else {VFalse},
+		}
 	}
 }
 
-#[derive (Debug)]
-struct VarMap {
-	lit_num: 			usize,
-	true_clause_list: 	Vec<VarPosList>,		//list of clauses where the variable is true
-	false_clause_list: 	Vec<VarPosList>,		//list of clauses where the variable is false
-	cnt: 				Vec<usize>,
+#[derive (Debug, Copy, Clone, PartialEq, Eq)]
+///Result of solving the CNF.
+pub enum SolveResult {
+	///The CNF is satisfiable. The model can be read by Solver::get_model.
+	Sat,
+	///The CNF is unsatisfiable, or unsatisfiable under the assumptions.
+	Unsat,
+	///The search stopped before the answer was found, because the budget ran out.
+	Unknown,
+	///The search was stopped through an InterruptHandle.
+	Interrupted,
 }
 
-type VarPos = (usize, usize);
-type VarPosList = Vec<VarPos>;
-
-impl VarMap {
-	fn new() -> Self {
-		VarMap {
-			lit_num: 0,
-			true_clause_list: 	Vec::<VarPosList>::new(),
-			false_clause_list: 	Vec::<VarPosList>::new(),
-			cnt: 				Vec::<usize>::new(),
-		}
-	}
-	
-	//add a new variable
-	fn new_var(&mut self) {
-		self.lit_num += 1;
-		self.true_clause_list.push(Vec::<VarPos>::new());
-		self.false_clause_list.push(Vec::<VarPos>::new());
-		self.cnt.push(0);
-	}
-	
-	//add a new clause
-	fn add_clause(&mut self, idx: usize, clause: &Clause) {
-		let lits = clause.get_all_lits();
-		for (i, lit) in lits.iter().enumerate() {
-			let var_num = lit.var_num();
-			self.cnt[var_num] += 1;
-			
-			if lit.get_value() == VTrue {
-				self.true_clause_list[var_num].push((idx, i));
-			}else {
-				self.false_clause_list[var_num].push((idx, i));
-			}
+impl fmt::Display for SolveResult {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			SolveResult::Sat => write!(f, "SAT"),
+			SolveResult::Unsat => write!(f, "UNSAT"),
+			SolveResult::Unknown => write!(f, "UNKNOWN"),
+			SolveResult::Interrupted => write!(f, "INTERRUPTED"),
 		}
 	}
-	
-	fn get_clauses_of(&self, var: usize, val: VarValue) -> &[VarPos] {
-		if val == VTrue {
-			&self.true_clause_list[var]
-		}else {
-			&self.false_clause_list[var]
-		}
+}
+
+#[derive (Debug, Copy, Clone, PartialEq, Eq)]
+///What Solver::add_clause_with_status does with a clause.
+pub enum ClauseStatus {
+	///The clause is added as it is, with its literals sorted by variable.
+	Kept,
+	///The clause is added without its duplicate literals and the literals which are false without
+	///any decision.
+	Simplified,
+	///The clause is not added, because it is a tautology or one of its literals is true without
+	///any decision.
+	Satisfied,
+}
+
+#[derive (Debug, Clone)]
+///Handle to stop a running solve from another thread, e.g. a Ctrl-C handler or a watchdog.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///use std::thread;
+///
+///let mut solver = Solver::new();
+///let handle = solver.interrupt_handle();
+///
+///thread::spawn(move || {
+///    handle.interrupt();
+///}).join().unwrap();
+///
+///println!("{}", solver.solve()); //INTERRUPTED
+///```
+pub struct InterruptHandle {
+	flag: 	Arc<AtomicBool>,
+}
+
+impl InterruptHandle {
+	///Ask the solver to stop. The running solve, or the next one if none is running, returns
+	///SolveResult::Interrupted at its next safe point and the solver stays usable.
+	pub fn interrupt(&self) {
+		self.flag.store(true, atomic::Ordering::Relaxed);
 	}
 }
 
 #[derive (Debug)]
 ///SAT Solver
 pub struct Solver {
-	cnf: 		CNF,	//CNF  
-	len: 		usize,
+	cnf: 		Cnf,	//CNF  
 	num_var: 	usize,	//number of variables
 	model: 		Model,
+	solution: 	Vec<VarValue>,	//the model found by the last successful solve
 	status: 	bool,	//if the model is UNSAT or not. status == false implies the CNF is UNSAT.
-	iter_num:	usize,
+	front_pt: 	usize,	//every variable before front_pt is assigned
+	order: 		VarOrder,	//activity based order of variables
+	assumptions:Vec<Lit>,	//literals assumed to be true during the current solve
+	failed: 	Vec<Lit>,	//assumptions responsible for the last UNSAT result
+	core: 		Option<Vec<CRef>>,	//original clauses responsible for the last UNSAT result
+	budget: 	Budget,		//limits on the work of each solve
+	interrupt: 	Arc<AtomicBool>,	//set by InterruptHandle to stop the search
+	stats: 		SolverStats,
+	observer: 	Option<Box<dyn SolverObserver + Send>>,	//callbacks of the search
+	proof: 		Option<ProofWriter>,	//DRAT proof of the learnt and deleted clauses
+	num_learnt: usize,		//number of learnt clauses in the CNF which can be removed
+	max_learnt: usize,		//limit of num_learnt before the learnt clauses are reduced
+	elim_stack: Vec<(Lit, Vec<Lit>)>,	//clauses removed by preprocessing, with the literal which satisfies each
+	modified: 	bool,		//if original clauses were added since the last preprocessing
+	next_vivify:u64,		//number of conflicts before the next vivification
+	vivify_pt: 	CRef,		//reference of the clause the next vivification starts from
+	config: 	SolverConfig,
+}
+
+impl Default for Solver {
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl Solver {
@@ -518,40 +635,186 @@ impl Solver {
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
 	///let vars = solver.create_vars(100);
-	///let x = create_lits(&vars);
+	///let x = Lit::create_lits(&vars);
 	///
 	///solver.add_clause_from_lits(vec![x[0], !x[2], x[80]]).unwrap();
 	///println!("{}", solver); //(0\/~2\/80)
 	///
-	///solver.add_clause_from_lits(vec![!x[57], x[24]).unwrap();
+	///solver.add_clause_from_lits(vec![!x[57], x[24]]).unwrap();
 	///println!("{}", solver); //(0\/~2\/80)/\(~57\/24)
 	///```
 	pub fn new() -> Self {
 		Solver {
-			cnf: 		CNF::new(),
-			len: 		0,
+			cnf: 		Cnf::new(),
 			num_var: 	0,
 			model: 		Model::new(),
+			solution: 	Vec::<VarValue>::new(),
 			status: 	true,
-			iter_num:	0,
+			front_pt: 	0,
+			order: 		VarOrder::new(),
+			assumptions:Vec::<Lit>::new(),
+			failed: 	Vec::<Lit>::new(),
+			core: 		None,
+			budget: 	Budget::default(),
+			interrupt: 	Arc::new(AtomicBool::new(false)),
+			stats: 		SolverStats::default(),
+			observer: 	None,
+			proof: 		None,
+			num_learnt: 0,
+			max_learnt: 0,
+			elim_stack: Vec::<(Lit, Vec<Lit>)>::new(),
+			modified: 	true,
+			next_vivify:0,
+			vivify_pt: 	0,
+			config: 	SolverConfig::default(),
+		}
+	}
+	
+	///Create a new SAT Solver with the given configuration
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let mut config = SolverConfig::default();
+	///config.branching = Branching::Ordered;
+	///
+	///let mut solver = Solver::with_config(config);
+	///```
+	pub fn with_config(config: SolverConfig) -> Self {
+		let mut solver = Solver::new();
+		solver.config = config;
+		solver
+	}
+	
+	///Return the configuration of the solver
+	pub fn get_config(&self) -> &SolverConfig {
+		&self.config
+	}
+	
+	///Set the limits on the work of each following call of solve. When a limit is reached,
+	///solve stops and returns SolveResult::Unknown, and the solver can still be used.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let mut solver = Solver::new();
+	///let x = Lit::create_lits(&solver.create_vars(2));
+	///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+	///
+	///solver.set_budget(Budget { decisions: Some(0), ..Default::default() });
+	///println!("{}", solver.solve()); //UNKNOWN
+	///
+	///solver.set_budget(Budget::default());
+	///println!("{}", solver.solve()); //SAT
+	///```
+	pub fn set_budget(&mut self, budget: Budget) {
+		self.budget = budget;
+	}
+	
+	///Return a handle which can stop a running solve from another thread
+	pub fn interrupt_handle(&self) -> InterruptHandle {
+		InterruptHandle {
+			flag: 	self.interrupt.clone(),
 		}
 	}
 	
+	///Register an observer which is called on the events of the search. It replaces the previous one.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///struct Tracer;
+	///
+	///impl SolverObserver for Tracer {
+	///    fn on_decision(&mut self, lit: Lit, level: usize) {
+	///        println!("decide {} at level {}", lit, level);
+	///    }
+	///}
+	///
+	///let mut solver = Solver::new();
+	///let x = Lit::create_lits(&solver.create_vars(2));
+	///solver.add_clause_from_lits(vec![!x[0], x[1]]).unwrap();
+	///solver.set_observer(Box::new(Tracer));
+	///solver.solve();
+	///```
+	pub fn set_observer(&mut self, observer: Box<dyn SolverObserver + Send>) {
+		self.observer = Some(observer);
+	}
+	
+	///Remove the registered observer and return it.
+	pub fn take_observer(&mut self) -> Option<Box<dyn SolverObserver + Send>> {
+		self.observer.take()
+	}
+	
+	///Write a DRAT proof of every following solve into the sink. The clauses added to the solver are
+	///the original clauses of the proof, so it should be set before the first solve.
+	///When a solve returns UNSAT without assumptions, the proof ends with the empty clause.
+	///The sink is written line by line, so a buffered writer is recommended.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///use std::fs::File;
+	///use std::io::BufWriter;
+	///
+	///let mut solver = Solver::new();
+	///let x = Lit::create_lits(&solver.create_vars(1));
+	///solver.add_clause_from_lits(vec![x[0]]).unwrap();
+	///solver.add_clause_from_lits(vec![!x[0]]).unwrap();
+	///
+	///let file = File::create(std::env::temp_dir().join("proof.drat")).unwrap();
+	///solver.set_proof(Box::new(BufWriter::new(file)), ProofFormat::Text);
+	///println!("{}", solver.solve()); //UNSAT
+	///solver.close_proof().unwrap();
+	///```
+	pub fn set_proof(&mut self, sink: Box<dyn Write + Send>, format: ProofFormat) {
+		self.proof = Some(ProofWriter::new(sink, format));
+	}
+	
+	///Stop writing the proof and flush its sink. Return the first error of writing the proof.
+	pub fn close_proof(&mut self) -> Result<(), String> {
+		match self.proof.take() {
+			Some(proof) => proof.finish(),
+			None => Ok(()),
+		}
+	}
+	
+	///Replace the configuration of the solver. It takes effect from the next call of solve.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let mut solver = Solver::new();
+	///let mut config = solver.get_config().clone();
+	///config.var_decay = 0.8;
+	///solver.set_config(config);
+	///```
+	pub fn set_config(&mut self, config: SolverConfig) {
+		self.config = config;
+	}
+	
 	///Create multiple variables
 	///
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
 	///let vars = solver.create_vars(100);
-	///let x = create_lits(&vars);
+	///let x = Lit::create_lits(&vars);
 	///
 	///solver.add_clause_from_lits(vec![x[0], !x[2], x[80]]).unwrap();
 	///println!("{}", solver); //(0\/~2\/80)
 	///
-	///solver.add_clause_from_lits(vec![!x[57], x[24]).unwrap();
+	///solver.add_clause_from_lits(vec![!x[57], x[24]]).unwrap();
 	///println!("{}", solver); //(0\/~2\/80)/\(~57\/24)
 	///```
 	pub fn create_vars(&mut self, num: usize) -> Vec<Var> {
@@ -564,11 +827,12 @@ impl Solver {
 		vars
 	}
 	
-	//Create a new variable
+	///Create a new variable
 	///
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
 	///let v0 = solver.new_var();
 	///let v1 = solver.new_var();
@@ -582,25 +846,65 @@ impl Solver {
 	pub fn new_var(&mut self) -> Var {
 		let num = self.num_var;
 		self.model.new_var();
+		self.order.new_var();
+		self.solution.push(VUndef);
 		self.num_var += 1;
 		Var::new(num)
 	}
 	
-	///Set the frequency of printing iteration number. If num is 0, solver will not print iterations number.
+	//remove the variable created last together with every clause containing it, including the learnt ones
+	//the other clauses must not be derived from those removed
+	fn remove_last_var(&mut self) {
+		let var = self.num_var - 1;
+		self.cancel_until(0);
+		//the removed clauses which contain it are added back, so that they are removed below
+		if self.elim_stack.iter().any(|(w, lits)| w.var_num() == var || lits.iter().any(|l| l.var_num() == var)) {
+			self.restore_eliminated();
+		}
+		for ci in self.cnf.refs() {
+			if self.cnf.lits(ci).iter().any(|l| l.var_num() == var) {
+				self.remove_clause(ci, None);
+			}
+		}
+		//its assignment without any decision implies nothing once its clauses are removed
+		if let Some(pos) = self.model.trail.iter().position(|l| l.var_num() == var) {
+			self.model.trail.remove(pos);
+			if self.model.qhead > pos {
+				self.model.qhead -= 1;
+			}
+		}
+		self.model.remove_last_var();
+		self.order.remove_last_var();
+		self.solution.truncate(var);
+		self.num_var = var;
+		self.front_pt = self.front_pt.min(var);
+		self.failed.retain(|l| l.var_num() != var);
+		self.collect_garbage();
+	}
+	
+	///Return the statistics accumulated over every solve of the solver.
 	///
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
-	///solver.set_iter_print_freq(1000); //print iteration number of every 1000 iterations.
+	///let x = Lit::create_lits(&solver.create_vars(2));
+	///solver.add_clause_from_lits(vec![!x[0], x[1]]).unwrap();
+	///solver.solve();
+	///println!("{}", solver.get_stats().solves); //1
 	///```
-	pub fn set_iter_print_freq(&mut self, num: usize) {
-		self.iter_num = num;
+	pub fn get_stats(&self) -> &SolverStats {
+		&self.stats
 	}
 	
 	///Add one clause into the solver. Return the solver is still satisfiable or not.
 	///False means USNAT.  
 	///
+	///Clauses can be added after solve. The learnt clauses, activities and saved phases are kept,
+	///and the next solve takes the new clause into account. The clause is simplified as described in
+	///add_clause_with_status.
+	///
 	///# Error
 	///
 	///This function will return error if the solver is already unsat.
@@ -608,6 +912,7 @@ impl Solver {
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
 	///let v0 = solver.new_var();
 	///let v1 = solver.new_var();
@@ -626,10 +931,92 @@ impl Solver {
 	///println!("{}", solver); //(0\/~1)/\(~0)
 	///```
 	pub fn add_clause(&mut self, clause: Clause) -> Result<bool, String> {
+		self.add_clause_with_status(clause)?;
+		Ok(self.status)
+	}
+	
+	///Add one clause into the solver like add_clause, and return what is done with it. The literals
+	///are sorted by variable, the duplicate ones are removed, and so are the ones which are false
+	///without any decision, unless the core is tracked. A tautology or a clause with a literal which
+	///is true without any decision is not added.
+	///
+	///# Error
+	///
+	///This function will return error if the solver is already unsat.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let mut solver = Solver::new();
+	///let x = Lit::create_lits(&solver.create_vars(3));
+	///
+	///let mut c0 = Clause::new();
+	///c0.push(x[2]);
+	///c0.push(!x[1]);
+	///c0.push(x[2]);
+	///println!("{:?}", solver.add_clause_with_status(c0).unwrap()); //Simplified
+	///
+	///let mut c1 = Clause::new();
+	///c1.push(x[0]);
+	///c1.push(!x[0]);
+	///println!("{:?}", solver.add_clause_with_status(c1).unwrap()); //Satisfied
+	///println!("{}", solver); //(~1\/2)
+	///```
+	pub fn add_clause_with_status(&mut self, clause: Clause) -> Result<ClauseStatus, String> {
+		if !self.status {
+			return Err("The model is already UNSAT".to_string());
+		}
+		//a variable of the clauses removed by preprocessing is used again
+		if clause.vec_lit.iter().any(|l| l.var_num() < self.num_var && self.model.witness[l.var_num()]) {
+			self.restore_eliminated();
+		}
+		if let Some(var_num) = clause.get_max() {
+			while var_num >= self.num_var {
+				self.new_var();
+			}
+		}
+		//the clause is simplified by the assignments which do not depend on any decision
+		self.cancel_until(0);
+		let len = clause.len();
+		let mut lits = clause.vec_lit;
+		lits.sort();
+		lits.dedup();
+		if lits.windows(2).any(|w| w[0].var_num() == w[1].var_num()) ||
+			lits.iter().any(|&l| self.model.lit_value(l) == VTrue) {
+			return Ok(ClauseStatus::Satisfied);
+		}
+		//the false literals are kept for the core, which contains the reasons of their assignments
+		if !self.config.core_tracking {
+			let before = lits.len();
+			lits.retain(|&l| self.model.lit_value(l) != VFalse);
+			//the empty clause is written by set_unsat
+			if lits.len() < before && !lits.is_empty() {
+				if let Some(ref mut proof) = self.proof {
+					proof.add(&lits);
+				}
+			}
+		}
+		let status = if lits.len() < len {ClauseStatus::Simplified} else {ClauseStatus::Kept};
+		
+		self.modified = true;
+		let mut clause = Clause::new();
+		for lit in lits {
+			clause.push(lit);
+		}
+		self.insert_clause(clause)?;
+		Ok(status)
+	}
+	
+	//add a clause into the CNF, without restoring the clauses removed by preprocessing
+	fn insert_clause(&mut self, clause: Clause) -> Result<bool, String> {
 		if self.status {
-			if clause.len() == 0 {
-				self.cnf.add_clause(clause);
-				self.status = false;
+			self.core = None;
+			//the clause is added on top of the assignments which do not depend on any decision
+			self.cancel_until(0);
+			if clause.is_empty() {
+				let cr = self.cnf.add_clause(&clause.vec_lit, false);
+				self.set_unsat(cr);
 				return Ok(self.status);
 			}
 			if let Some(var_num) = clause.get_max() {
@@ -644,22 +1031,34 @@ impl Solver {
 				if lit.get_value().equals(self.model.var[lit.var_num()]) {
 					//if such an assignment has been performed before
 					if self.model.var[lit.var_num()] == VUndef {
-						self.model.var[lit.var_num()] = lit.get_value();
-						
-						self.model.map.add_clause(self.cnf.len(), &clause);
-						self.len += 1;
-						self.cnf.add_clause(clause);
+						let cr = self.attach_clause(&clause.vec_lit, false);
+						self.assign(lit, Some(cr));
 					}
 				}else {
 					//conflict assignment
-					self.cnf.add_clause(clause);
-					self.status = false;
+					let cr = self.cnf.add_clause(&clause.vec_lit, false);
+					self.set_unsat(cr);
 				}
 			}else {
+				//move the literals which are not false to the front, so that they are watched
+				let mut clause = clause;
+				let mut free = 0;
+				for i in 0..clause.vec_lit.len() {
+					if self.model.lit_value(clause.vec_lit[i]) != VFalse {
+						clause.vec_lit.swap(free, i);
+						free += 1;
+					}
+				}
+				let first = clause.vec_lit[0];
 				//add a regular clause into the solver
-				self.model.map.add_clause(self.cnf.len(), &clause);
-				self.len += 1;
-				self.cnf.add_clause(clause);
+				let cr = self.attach_clause(&clause.vec_lit, false);
+				match free {
+					//every literal is already false
+					0 => self.set_unsat(cr),
+					//the clause becomes an assignment
+					1 if self.model.lit_value(first) == VUndef => self.assign(first, Some(cr)),
+					_ => {},
+				}
 			}
 			Ok(self.status)
 		}else {
@@ -677,6 +1076,7 @@ impl Solver {
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
 	///let v0 = solver.new_var();
 	///let v1 = solver.new_var();
@@ -695,11 +1095,13 @@ impl Solver {
 		self.add_clause(c)
 	}
 	
-	///Return the model (value of variables) of CNF.
+	///Return the model (value of variables) of CNF found by the last successful solve.
+	///The model is kept until the next successful solve, even if more clauses are added.
 	///
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
 	///let v0 = solver.new_var();
 	///let v1 = solver.new_var();
@@ -723,14 +1125,97 @@ impl Solver {
 	///println!("{}", model[1]); //F
 	///```
 	pub fn get_model(&self) -> &[VarValue] {
-		&self.model.var
+		&self.solution
 	}
 	
-	///Return a list of original clauses.
+	///Enumerate the models one by one. Each model differs from the previous ones on the projection
+	///variables, which are all the current variables if projection is None. At most limit models are found.
+	///The learnt clauses are kept between the models, so the search does not start from scratch.
+	///
+	///The enumeration stops when no more model exists, or a solve returns Unknown or Interrupted.
+	///A new variable is created to guard the blocking clauses, which are disabled when the iterator is dropped.
 	///
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
+	///let mut solver = Solver::new();
+	///let v = solver.create_vars(3);
+	///let x = Lit::create_lits(&v);
+	///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+	///
+	///println!("{}", solver.models(None, None).count()); //6
+	///println!("{}", solver.models(Some(&v[..2]), None).count()); //3
+	///println!("{}", solver.models(None, Some(2)).count()); //2
+	///
+	///for model in solver.models(Some(&v[..1]), None) {
+	///    println!("{}", model[0]); //T, F
+	///}
+	///```
+	pub fn models(&mut self, projection: Option<&[Var]>, limit: Option<usize>) -> Models<'_> {
+		Models::new(self, projection, limit)
+	}
+	
+	///Count the models of the original clauses exactly, on the projection variables if it is given.
+	///See ModelCounter.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let mut solver = Solver::new();
+	///let v = solver.create_vars(100);
+	///let x = Lit::create_lits(&v);
+	///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+	///
+	///println!("{}", solver.count_models(None)); //950737950171172051122527404032
+	///println!("{}", solver.count_models(Some(&v[..2]))); //3
+	///```
+	pub fn count_models(&self, projection: Option<&[Var]>) -> BigNum {
+		let mut counter = ModelCounter::new(&self.get_oringin_clauses(), self.num_var);
+		if let Some(vars) = projection {
+			counter.set_projection(vars);
+		}
+		counter.count()
+	}
+	
+	///Search for a model of the original clauses by stochastic local search. If it is found,
+	///return SAT and get_model returns it. Otherwise return UNKNOWN, or UNSAT if the solver already
+	///knows the CNF is UNSAT. See LocalSearch.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let mut solver = Solver::new();
+	///let x = Lit::create_lits(&solver.create_vars(2));
+	///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+	///solver.add_clause_from_lits(vec![!x[0]]).unwrap();
+	///
+	///let config = SlsConfig { algorithm: SlsAlgorithm::ProbSat { cb: 2.5 }, ..Default::default() };
+	///println!("{}", solver.local_search(config)); //SAT
+	///solver.print_model(); //FT
+	///```
+	pub fn local_search(&mut self, config: SlsConfig) -> SolveResult {
+		if !self.status {
+			return SolveResult::Unsat;
+		}
+		let mut sls = LocalSearch::new(&self.get_oringin_clauses(), self.num_var, config);
+		let result = sls.solve();
+		if result == SolveResult::Sat {
+			self.solution = sls.get_model().to_vec();
+		}
+		result
+	}
+	
+	///Return a list of original clauses. The literals of a clause may be reordered by the solver.
+	///After preprocessing, the clauses it derived are included and the clauses it removed come last,
+	///so that they are still equivalent to the clauses added.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
 	///let v0 = solver.new_var();
 	///let v1 = solver.new_var();
@@ -747,14 +1232,32 @@ impl Solver {
 	///solver.add_clause(c0).unwrap();
 	///solver.add_clause(c1).unwrap();
 	///
-	///solver.siimplify();
+	///solver.simplify();
 	///
 	///let clauses = solver.get_oringin_clauses();
-	///println!("{}", clauses[0]); //(0\/~1)
+	///println!("{}", clauses[0]); //(~1\/0)
 	///println!("{}", clauses[1]); //(~0)
 	///```
 	pub fn get_oringin_clauses(&self) -> Vec<Clause> {
-		self.cnf.clauses.clone()
+		let mut clauses = Vec::<Clause>::new();
+		for cr in self.cnf.refs() {
+			if !self.cnf.is_learnt(cr) {
+				let mut clause = Clause::new();
+				for &lit in self.cnf.lits(cr) {
+					clause.push(lit);
+				}
+				clauses.push(clause);
+			}
+		}
+		//the clauses removed by preprocessing
+		for (_, lits) in &self.elim_stack {
+			let mut clause = Clause::new();
+			for &lit in lits {
+				clause.push(lit);
+			}
+			clauses.push(clause);
+		}
+		clauses
 	}
 	
 	///Print the model (value of variables) of CNF.
@@ -762,6 +1265,7 @@ impl Solver {
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
 	///let v0 = solver.new_var();
 	///let v1 = solver.new_var();
@@ -779,11 +1283,11 @@ impl Solver {
 	///solver.add_clause(c1).unwrap();
 	///
 	///solver.solve();
-	///solve.print_model(); //FF
+	///solver.print_model(); //FF
 	///```
 	pub fn print_model(&self) {
 		if self.status {
-			for var in self.model.var.iter() {
+			for var in self.solution.iter() {
 				print!("{}", var);
 			}
 			println!();
@@ -795,9 +1299,13 @@ impl Solver {
 	///Simplify the CNF. Return if the CNF is still satisfiable.
 	///False means UNSAT.
 	///
+	///The assignments which do not depend on any decision are propagated, then the preprocessing
+	///enabled in SolverConfig runs if clauses were added since it last ran. It is called by solve.
+	///
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
 	///let v0 = solver.new_var();
 	///let v1 = solver.new_var();
@@ -817,145 +1325,402 @@ impl Solver {
 	///solver.add_clause(c0).unwrap();
 	///solver.add_clause(c1).unwrap();
 	///
-	///println!("{}", solver); //(0\/~1\/2)/\(~0)
-	///let sat = solver.siimplify();
+	///println!("{}", solver); //(~1\/2)
+	///let sat = solver.simplify();
 	///if sat {
-	///	println!("{}", solver); //(~1\/2)
+	///    println!("{}", solver); //(~1\/2)
 	///}else {
-	///	println!("UNSAT");
+	///    println!("UNSAT");
 	///}
 	///```
-	pub fn simplify(&mut self) -> bool{
+	pub fn simplify(&mut self) -> bool {
 		if self.status {
-			loop {
-				let mut propagated = false;
-				for i in 0..self.num_var {
-					//if the currented var is true and not propagated yet
-					if !self.model.propagated[i] {
-						propagated = true;
-						let var = self.model.var[i];
-						if var != VUndef {
-							//propagate the value forward
-							let empty = self.propagate(i, var, true, None);
-							if empty {
-								self.status = false;
-								return false;
-							}
-						}
-					}
-				}
-				//if no propagation is performed or there is no more unit clause
-				if !propagated || !self.perform_assignments() {
-					break;
-				}
+			let start_time = Instant::now();
+			self.cancel_until(0);
+			if let Some(confl) = self.propagate() {
+				self.set_unsat(confl);
+			}else {
+				self.preprocess();
+				self.collect_garbage();
 			}
+			self.stats.simplify_time += start_time.elapsed();
 		}
 		self.status
 	}
 	
-	//check if there is any assignment (unit clause) in the CNF
-	fn perform_assignments(&mut self) -> bool {
-		let mut result = false;
-		for i in 0..self.cnf.len() {
-			if self.cnf.sat[i] == 0 && self.cnf.clauses[i].len() == 1 {
-				result = true;
-				let lit = self.cnf.clauses[i].get_first().unwrap();
-				self.model.var[lit.var_num()] = lit.get_value(); 
+	//mark the CNF as UNSAT because of a clause falsified without any decision
+	//the proof ends with the empty clause
+	fn set_unsat(&mut self, confl: CRef) {
+		self.status = false;
+		if let Some(ref mut proof) = self.proof {
+			proof.add(&[]);
+		}
+		if self.config.core_tracking {
+			self.trace_core(vec![confl]);
+		}
+	}
+	
+	//collect the original clauses the given clauses are derived from, together with the reasons of
+	//their literals falsified without any decision
+	//the core is not found if a learnt clause was learnt without tracking
+	fn trace_core(&mut self, mut clauses: Vec<CRef>) {
+		let mut visited = vec![false; self.cnf.size()];
+		let mut explained = vec![false; self.num_var];
+		let mut core = Vec::<CRef>::new();
+		while let Some(ci) = clauses.pop() {
+			if visited[ci as usize] {
+				continue;
+			}
+			visited[ci as usize] = true;
+			if !self.cnf.is_learnt(ci) {
+				core.push(ci);
+			}else {
+				match self.cnf.antecedents(ci) {
+					Some(antecedents) => clauses.extend(antecedents),
+					None => {
+						self.core = None;
+						return;
+					},
+				}
+			}
+			for &lit in self.cnf.lits(ci) {
+				let var = lit.var_num();
+				if self.model.level[var] == 0 && self.model.lit_value(lit) == VFalse && !explained[var] {
+					explained[var] = true;
+					if let Some(reason) = self.model.reason[var] {
+						clauses.push(reason);
+					}
+				}
 			}
 		}
-		result
+		core.sort();
+		self.core = Some(core);
 	}
 	
-	//propagate the value throughout the CNF
-	//forward: true means perform the propagation, false means undo the propagation
-	fn propagate(&mut self, var: usize, value: VarValue, forward: bool, mut assignment_set: Option<&mut HashSet<usize>>) -> bool {
-		self.model.propagated[var] = forward;
-		let sat_list;
-		let unsat_list;
-		let mut result = false;
-		
-		if value == VTrue {
-			sat_list = self.model.map.get_clauses_of(var, VTrue);
-			unsat_list = self.model.map.get_clauses_of(var, VFalse);
-		}else {
-			sat_list = self.model.map.get_clauses_of(var, VFalse);
-			unsat_list = self.model.map.get_clauses_of(var, VTrue);
+	//add a clause into the CNF, and watch its first two literals
+	fn attach_clause(&mut self, lits: &[Lit], learnt: bool) -> CRef {
+		let cr = self.cnf.add_clause(lits, learnt);
+		if lits.len() >= 2 {
+			for i in 0..2 {
+				let watcher = Watcher {
+					clause: 	cr,
+					blocker: 	lits[1 - i],
+				};
+				self.model.watches[lits[i].index()].push(watcher);
+			}
 		}
-
-		for j in sat_list {
-			if forward {
-				if self.cnf.sat[j.0] == 0 {
-					self.len -= 1;
+		if !learnt {
+			for lit in lits {
+				self.model.occurs[lit.index()] += 1;
+			}
+		}
+		cr
+	}
+	
+	//assign a literal at the current decision level
+	fn assign(&mut self, lit: Lit, reason: Option<CRef>) {
+		let var = lit.var_num();
+		self.model.var[var] = lit.get_value();
+		self.model.level[var] = self.model.decision_level();
+		self.model.reason[var] = reason;
+		self.model.trail.push(lit);
+	}
+	
+	//undo all assignments above the given decision level
+	fn cancel_until(&mut self, level: usize) {
+		if self.model.decision_level() > level {
+			let lim = self.model.trail_lim[level];
+			for i in lim..self.model.trail.len() {
+				let var = self.model.trail[i].var_num();
+				if self.config.phase_saving {
+					self.model.phase[var] = self.model.var[var];
 				}
-				//remove the clause
-				self.cnf.sat[j.0] += 1;
-			}else {
-				//restore the clause
-				self.cnf.sat[j.0] -= 1;
-				if self.cnf.sat[j.0] == 0 {
-					self.len += 1;
+				self.model.var[var] = VUndef;
+				self.model.reason[var] = None;
+				if var < self.front_pt {
+					self.front_pt = var;
 				}
+				self.order.insert(var);
 			}
+			self.model.trail.truncate(lim);
+			self.model.trail_lim.truncate(level);
+			self.model.qhead = lim;
 		}
-		for j in unsat_list {
-			if forward {
-				if self.cnf.sat[j.0] == 0 {
-					//remove the literal from the clause
-					self.cnf.clauses[j.0].remove(j.1);
-					//check if the clause is empty
-					let len = self.cnf.clauses[j.0].len();
-					if len == 0 {
-						result = true;
-					//check if the clause becomes an assignment	
-					}else if len == 1 {
-						//get the lit from the assignment
-						let lit = self.cnf.clauses[j.0].get_first().unwrap();
-						let var = lit.var_num();
-						let value = lit.get_value();
-						//add assignment count by one
-						self.model.expected[var].1 += 1;
-						
-						if let Some(ref mut set) = assignment_set {
-							set.insert(var);
-						}
-						
-						//check if there is a conflict on the assignment
-						if self.model.expected[var].0.equals(value) {
-							self.model.expected[var].0 = value;
-						}else {
-							result = true;
-						}
-					}
+	}
+	
+	//propagate all pending assignments throughout the CNF
+	//return the index of the conflict clause if there is one
+	fn propagate(&mut self) -> Option<CRef> {
+		let mut confl = None;
+		while confl.is_none() && self.model.qhead < self.model.trail.len() {
+			let false_lit = !self.model.trail[self.model.qhead];
+			self.model.qhead += 1;
+			self.stats.propagations += 1;
+			
+			//only the clauses watching the falsified literal need to be visited
+			let mut ws = mem::take(&mut self.model.watches[false_lit.index()]);
+			let mut i = 0;
+			let mut j = 0;
+			'next_watcher: while i < ws.len() {
+				let w = ws[i];
+				i += 1;
+				//the clause is already satisfied
+				if self.model.lit_value(w.blocker) == VTrue {
+					ws[j] = w;
+					j += 1;
+					continue;
 				}
-			}else {
-				//check if the clause is an assignment
-				if self.cnf.sat[j.0] == 0 && self.cnf.clauses[j.0].len() == 1 {
-					let lit = self.cnf.clauses[j.0].get_first().unwrap();
-					let var = lit.var_num();
-					self.model.expected[var].1 -= 1;
-					
-					//undo assignment
-					if self.model.expected[var].1 == 0 {
-						self.model.expected[var].0 = VUndef;
-						if let Some(ref mut set) = assignment_set {
-							set.remove(&var);
-						}
+				
+				//make sure the false literal is the second one
+				let lits = self.cnf.lits_mut(w.clause);
+				if lits[0] == false_lit {
+					lits.swap(0, 1);
+				}
+				let first = lits[0];
+				let watcher = Watcher {
+					clause: 	w.clause,
+					blocker: 	first,
+				};
+				if first != w.blocker && self.model.lit_value(first) == VTrue {
+					ws[j] = watcher;
+					j += 1;
+					continue;
+				}
+				
+				//look for a new literal to watch
+				for k in 2..lits.len() {
+					if self.model.lit_value(lits[k]) != VFalse {
+						lits.swap(1, k);
+						self.model.watches[lits[1].index()].push(watcher);
+						continue 'next_watcher;
 					}
 				}
 				
-				//restore the literal in the clause
-				self.cnf.clauses[j.0].restore(j.1);
+				//the clause is unit or empty under the current assignment
+				ws[j] = watcher;
+				j += 1;
+				if self.model.lit_value(first) == VFalse {
+					confl = Some(w.clause);
+					while i < ws.len() {
+						ws[j] = ws[i];
+						i += 1;
+						j += 1;
+					}
+				}else {
+					self.assign(first, Some(w.clause));
+				}
 			}
+			ws.truncate(j);
+			self.model.watches[false_lit.index()] = ws;
 		}
-		result
+		confl
+	}
+	
+	//analyze the conflict and return a learnt clause, which is asserting at the first UIP,
+	//together with the level to backjump to
+	//the clauses resolved are returned if the core is tracked
+	fn analyze(&mut self, confl: CRef) -> (Vec<Lit>, usize, Option<Vec<CRef>>) {
+		let level = self.model.decision_level();
+		let mut antecedents = Vec::<CRef>::new();
+		let mut learnt = Vec::<Lit>::new();
+		let mut path_cnt = 0;		//number of literals of the current level still to resolve
+		let mut uip = None;
+		let mut idx = self.model.trail.len();
+		let mut confl = confl;
+		
+		loop {
+			antecedents.push(confl);
+			for &lit in self.cnf.lits(confl) {
+				let var = lit.var_num();
+				//skip the literal implied by this reason
+				if uip.is_some_and(|p: Lit| p.var_num() == var) {
+					continue;
+				}
+				if !self.model.seen[var] && self.model.level[var] > 0 {
+					self.model.seen[var] = true;
+					if self.config.branching == Branching::Vsids {
+						self.order.bump(var);
+					}
+					if self.model.level[var] == level {
+						path_cnt += 1;
+					}else {
+						learnt.push(lit);
+					}
+				}
+			}
+			//find the next literal of the current level to resolve on
+			loop {
+				idx -= 1;
+				if self.model.seen[self.model.trail[idx].var_num()] {
+					break;
+				}
+			}
+			let p = self.model.trail[idx];
+			self.model.seen[p.var_num()] = false;
+			path_cnt -= 1;
+			uip = Some(p);
+			if path_cnt == 0 {
+				break;
+			}
+			confl = self.model.reason[p.var_num()].unwrap();
+		}
+		
+		//drop literals implied by the other literals of the learnt clause
+		let mut keep = Vec::<Lit>::with_capacity(learnt.len() + 1);
+		keep.push(!uip.unwrap());
+		for &lit in &learnt {
+			if !self.is_redundant(lit) {
+				keep.push(lit);
+			}else if let Some(reason) = self.model.reason[lit.var_num()] {
+				antecedents.push(reason);
+			}
+		}
+		for lit in learnt {
+			self.model.seen[lit.var_num()] = false;
+		}
+		
+		//put the literal with the highest level at the second position
+		let mut bt_level = 0;
+		for i in 1..keep.len() {
+			if self.model.level[keep[i].var_num()] > bt_level {
+				bt_level = self.model.level[keep[i].var_num()];
+				keep.swap(1, i);
+			}
+		}
+		let antecedents = if self.config.core_tracking {Some(antecedents)} else {None};
+		(keep, bt_level, antecedents)
+	}
+	
+	//check if a literal of the learnt clause is implied by the other marked literals
+	fn is_redundant(&self, lit: Lit) -> bool {
+		match self.model.reason[lit.var_num()] {
+			Some(ci) => self.cnf.lits(ci).iter().all(|&l| {
+				let var = l.var_num();
+				var == lit.var_num() || self.model.seen[var] || self.model.level[var] == 0
+			}),
+			None => false,
+		}
+	}
+	
+	//pick the next decision literal, None if every variable is assigned
+	fn decide(&mut self) -> Option<Lit> {
+		let next_var = match self.config.branching {
+			Branching::Vsids => loop {
+				match self.order.pop() {
+					Some(var) => if self.model.var[var] == VUndef && !self.model.eliminated[var] {
+						break var;
+					},
+					None => return None,
+				}
+			},
+			Branching::Ordered => {
+				while self.front_pt < self.num_var && (self.model.var[self.front_pt] != VUndef || self.model.eliminated[self.front_pt]) {
+					self.front_pt += 1;
+				}
+				if self.front_pt == self.num_var {
+					return None;
+				}
+				self.front_pt
+			},
+		};
+		
+		//use the saved phase of the next var
+		if self.model.phase[next_var] != VUndef {
+			Some(Lit::create(next_var, self.model.phase[next_var] == VTrue))
+		//check if the next var only can be false
+		}else if self.model.occurs[Lit::create(next_var, true).index()] == 0 {
+			Some(Lit::create(next_var, false))
+		}else {
+			Some(Lit::create(next_var, true))
+		}
+	}
+	
+	//number of distinct decision levels among the literals
+	fn compute_lbd(&mut self, lits: &[Lit]) -> usize {
+		//repeated assumptions open empty decision levels, so there can be more levels than variables
+		let levels = self.model.decision_level() + 1;
+		if self.model.level_stamp.len() < levels {
+			self.model.level_stamp.resize(levels, 0);
+		}
+		self.model.stamp += 1;
+		let mut lbd = 0;
+		for lit in lits {
+			let level = self.model.level[lit.var_num()];
+			if self.model.level_stamp[level] != self.model.stamp {
+				self.model.level_stamp[level] = self.model.stamp;
+				lbd += 1;
+			}
+		}
+		lbd
+	}
+	
+	//check if the clause is the reason of a current assignment
+	fn is_locked(&self, ci: CRef) -> bool {
+		let lit = self.cnf.lits(ci)[0];
+		self.model.lit_value(lit) == VTrue && self.model.reason[lit.var_num()] == Some(ci)
+	}
+	
+	//move the clauses to the front of the arena once the removed ones take half of it, and update
+	//the references in the watches and the reasons
+	//the clauses stay in place while the core is tracked, as the learnt clauses refer to their antecedents
+	fn collect_garbage(&mut self) {
+		if self.config.core_tracking || self.cnf.wasted() * 2 <= self.cnf.size() {
+			return;
+		}
+		let moved = self.cnf.collect_garbage();
+		for ws in self.model.watches.iter_mut() {
+			*ws = ws.iter().filter_map(|w| arena::relocate(&moved, w.clause).map(|clause| Watcher {
+				clause,
+				blocker: 	w.blocker,
+			})).collect();
+		}
+		for reason in self.model.reason.iter_mut() {
+			*reason = reason.and_then(|cr| arena::relocate(&moved, cr));
+		}
+		//the next vivification starts from the first clause left after the old reference
+		let i = moved.partition_point(|&(old, _)| old < self.vivify_pt);
+		self.vivify_pt = moved.get(i).map_or(self.cnf.size() as CRef, |&(_, new)| new);
+	}
+	
+	//remove about half of the learnt clauses, keeping those with small LBD and those which are reasons
+	fn reduce_db(&mut self) {
+		let start_time = Instant::now();
+		let mut candidates = Vec::<CRef>::new();
+		for ci in self.cnf.refs() {
+			if self.cnf.is_learnt(ci) && self.cnf.lits(ci).len() > 2 {
+				candidates.push(ci);
+			}
+		}
+		//clauses with the largest LBD come first
+		candidates.sort_by_key(|&ci| Reverse(self.cnf.lbd(ci)));
+		
+		let limit = candidates.len() / 2;
+		for &ci in candidates.iter().take(limit) {
+			//glue clauses are always kept
+			if self.cnf.lbd(ci) > 2 && !self.is_locked(ci) {
+				if let Some(ref mut proof) = self.proof {
+					proof.delete(self.cnf.lits(ci));
+				}
+				self.cnf.delete(ci);
+				self.num_learnt -= 1;
+				self.stats.deleted_clauses += 1;
+			}
+		}
+		let cnf = &self.cnf;
+		for ws in self.model.watches.iter_mut() {
+			ws.retain(|w| !cnf.is_deleted(w.clause));
+		}
+		self.collect_garbage();
+		self.stats.reductions += 1;
+		self.stats.reduce_time += start_time.elapsed();
 	}
 
-	///Solve the CNF. Return if the CNF is still satisfiable.
-	///False means UNSAT.
+	///Solve the CNF. Return if the CNF is satisfiable, or Unknown if the budget runs out.
 	///
 	///# Examples
 	///
 	///```
+	///# use sat::sat_lib::*;
 	///let mut solver = Solver::new();
 	///let v0 = solver.new_var();
 	///let v1 = solver.new_var();
@@ -972,130 +1737,389 @@ impl Solver {
 	///solver.add_clause(c0).unwrap();
 	///solver.add_clause(c1).unwrap();
 	///
-	///let sat = solver.solve();
-	///if sat {
-	///	solve.print_model(); //FF
+	///if solver.solve() == SolveResult::Sat {
+	///    solver.print_model(); //FF
 	///}
 	///```
-	pub fn solve(&mut self) -> bool {
-		if self.status {
-			if !self.simplify() {
-				return false;
+	pub fn solve(&mut self) -> SolveResult {
+		self.solve_with_assumptions(&[])
+	}
+	
+	///Solve the CNF under the assumption that the given literals are true. Return if the CNF is
+	///satisfiable under the assumptions, or Unknown if the budget runs out. If it is UNSAT,
+	///get_failed_assumptions returns the assumptions responsible for it.
+	///
+	///Assumptions only hold during this call. Unlike unit clauses, they do not change the CNF,
+	///so the solver can be asked again under different assumptions.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let mut solver = Solver::new();
+	///let x = Lit::create_lits(&solver.create_vars(3));
+	///
+	///solver.add_clause_from_lits(vec![!x[0], x[1]]).unwrap();
+	///solver.add_clause_from_lits(vec![!x[1], !x[2]]).unwrap();
+	///
+	///println!("{}", solver.solve_with_assumptions(&[x[0], x[2]])); //UNSAT
+	///println!("{:?}", solver.get_failed_assumptions().len()); //2
+	///
+	///println!("{}", solver.solve_with_assumptions(&[x[0]])); //SAT
+	///solver.print_model(); //TTF
+	///```
+	pub fn solve_with_assumptions(&mut self, assumptions: &[Lit]) -> SolveResult {
+		let start_time = Instant::now();
+		self.stats.solves += 1;
+		self.failed.clear();
+		if !self.status {
+			return SolveResult::Unsat;
+		}
+		self.core = None;
+		for lit in assumptions {
+			while lit.var_num() >= self.num_var {
+				self.new_var();
 			}
-			let mut hist = Vec::<(Lit, Option<Lit>)>::new();	//history stack
-			let mut cnt = 0;	//iteration count
-			let mut next_lit = None;
-			let mut front_pt = 0;
-			let mut assignment_set = HashSet::<usize>::new();
-			
-			loop {
-				cnt += 1;
-				if self.iter_num != 0 && cnt % self.iter_num == 0 {
-					println!("Iteration: {}", cnt);
+		}
+		if assumptions.iter().any(|l| self.model.witness[l.var_num()]) {
+			self.restore_eliminated();
+		}
+		//start from the assignments which do not depend on any decision
+		//the assumptions are not eliminated by preprocessing
+		self.assumptions = assumptions.to_vec();
+		if !self.simplify() {
+			self.assumptions.clear();
+			self.stats.solve_time += start_time.elapsed();
+			return SolveResult::Unsat;
+		}
+		
+		//the budget limits the work of this call only
+		let start_stats = self.stats.clone();
+		let mut restart = RestartPolicy::new(self.config.restart);
+		if self.max_learnt == 0 {
+			self.max_learnt = self.config.reduce_first;
+		}
+		
+		//the search time is counted at each restart, so it can be read while the search runs
+		let mut search_time = Instant::now();
+		let result = loop {
+			//propagate()
+			//propagate the assignments and get if there is any empty clause
+			if let Some(confl) = self.propagate() {
+				//return UNSAT
+				//check if the conflict does not depend on any decision
+				if self.model.decision_level() == 0 {
+					self.set_unsat(confl);
+					break SolveResult::Unsat;
 				}
-				
-				//decide()
-				//check if need to find a new var to propagate
-				if next_lit == None {
-					while self.model.propagated[front_pt] {
-						front_pt += 1;
+				self.stats.conflicts += 1;
+				if let Some(ref mut observer) = self.observer {
+					observer.on_conflict(self.model.decision_level());
+					let interval = self.config.progress_interval;
+					if interval != 0 && self.stats.conflicts.is_multiple_of(interval) {
+						observer.on_progress(&self.stats);
 					}
-					let mut next_var = front_pt;
-					
-					if !assignment_set.is_empty() {
-						for i in assignment_set.iter() {
-							next_var = *i;
-							break;
-						}
-						assignment_set.remove(&next_var);
+				}
+				//analyze()
+				let (learnt, bt_level, antecedents) = self.analyze(confl);
+				self.stats.learnt_clauses += 1;
+				self.stats.learnt_literals += learnt.len() as u64;
+				
+				//the levels of the learnt clause are counted before they are undone
+				let lbd = self.compute_lbd(&learnt);
+				
+				//backtrack()
+				//undo assignments up to the second highest level of the learnt clause
+				self.cancel_until(bt_level);
+				let lit = learnt[0];
+				if let Some(ref mut proof) = self.proof {
+					proof.add(&learnt);
+				}
+				let ci = self.attach_clause(&learnt, true);
+				self.cnf.set_lbd(ci, lbd);
+				if let Some(antecedents) = antecedents {
+					self.cnf.set_antecedents(ci, antecedents);
+				}
+				if let Some(ref mut observer) = self.observer {
+					observer.on_learnt(&learnt, lbd);
+				}
+				if learnt.len() > 2 {
+					self.num_learnt += 1;
+				}
+				self.assign(lit, Some(ci));
+				self.order.decay(self.config.var_decay);
+				restart.on_conflict(lbd);
+			}else {
+				//restart()
+				//undo all decisions, the learnt clauses and saved phases are kept
+				if restart.should_restart() {
+					self.cancel_until(0);
+					restart.on_restart();
+					self.stats.restarts += 1;
+					self.stats.search_time += search_time.elapsed();
+					search_time = Instant::now();
+					if let Some(ref mut observer) = self.observer {
+						observer.on_restart(&self.stats);
 					}
-					
-					//check if the next var only can be false
-					if self.model.map.get_clauses_of(next_var, VTrue).len() == 0 || self.model.expected[next_var].0 == VFalse {
-						let lit = Lit::create(next_var, false);
-						next_lit = Some((lit, None));
-					//check if the next var	only can be true
-					}else if self.model.map.get_clauses_of(next_var, VFalse).len() == 0  || self.model.expected[next_var].0 == VTrue {
-						let lit = Lit::create(next_var, true);
-						next_lit = Some((lit, None));
-					//the value can be either true or false	
-					}else {
-						let lit = Lit::create(next_var, true);
-						next_lit = Some((lit, Some(!lit)));
+					//vivify()
+					//shorten the clauses without any decision, which may find the CNF UNSAT
+					self.vivify_clauses();
+					if !self.status {
+						break SolveResult::Unsat;
 					}
 				}
-
-				let lit = next_lit.unwrap().0;
-				let var = lit.var_num();
-				let value;
-				
-				//check if is an assignment from original CNF
-				if self.model.var[var] == VUndef {
-					value = lit.get_value();
-					self.model.var[var] = value;
-					//add the propagation into history stack
-					hist.push(next_lit.unwrap());
-				}else {
-					//use the value from the original CNF assignment
-					value = self.model.var[var];
-					next_lit = None;
+				if self.num_learnt >= self.max_learnt {
+					self.reduce_db();
+					self.max_learnt += self.config.reduce_inc;
 				}
 				
-				//propagate()
-				//propagate the value and get if there is any empty clause
-				let empty_clause = self.propagate(var, value, true, Some(&mut assignment_set));
+				//return UNKNOWN
+				//check if the budget runs out
+				let budget = self.budget;
+				if budget.conflicts.is_some_and(|n| self.stats.conflicts - start_stats.conflicts >= n) ||
+					budget.decisions.is_some_and(|n| self.stats.decisions - start_stats.decisions >= n) ||
+					budget.propagations.is_some_and(|n| self.stats.propagations - start_stats.propagations >= n) ||
+					budget.time.is_some_and(|t| start_time.elapsed() >= t) {
+					self.cancel_until(0);
+					break SolveResult::Unknown;
+				}
+				//return INTERRUPTED
+				if self.interrupt.swap(false, atomic::Ordering::Relaxed) ||
+					self.observer.as_mut().is_some_and(|o| o.should_stop()) {
+					self.cancel_until(0);
+					break SolveResult::Interrupted;
+				}
 				
-				//check if there is conflict
-				if empty_clause {
-					//backtrack()
-					//undo propagation based on history stack
-					while let Some((lit, next)) = hist.pop() {
-						let var = lit.var_num();
-						let val = lit.get_value();
-						
-						if var < front_pt {
-							front_pt = var;
-						}
-						//undo propagation
-						self.propagate(var, val, false, Some(&mut assignment_set));
-						self.model.var[var] = VUndef;
-						match next {
-							//if the var can be another value, use that value for next iteration
-							Some(lit) => {next_lit = Some((lit, None)); break;},
-							//return UNSAT
-							//check if the history is empty
-							None => if hist.len() == 0 {self.status = false;return false;},
-						}
+				//decide()
+				//the assumptions are decided first, one on each decision level
+				let mut next_lit = None;
+				while self.model.decision_level() < self.assumptions.len() {
+					let lit = self.assumptions[self.model.decision_level()];
+					match self.model.lit_value(lit) {
+						//the assumption already holds, open an empty decision level for it
+						VTrue => self.model.trail_lim.push(self.model.trail.len()),
+						VUndef => {
+							next_lit = Some(lit);
+							break;
+						},
+						VFalse => break,
 					}
-				}else {
+				}
+				if next_lit.is_none() && self.model.decision_level() < self.assumptions.len() {
+					//return UNSAT
+					//the assumption is falsified by the others
+					let lit = self.assumptions[self.model.decision_level()];
+					self.analyze_final(lit);
+					break SolveResult::Unsat;
+				}
+				if next_lit.is_none() {
+					next_lit = self.decide();
+				}
+				match next_lit {
+					Some(lit) => {
+						self.stats.decisions += 1;
+						self.model.trail_lim.push(self.model.trail.len());
+						self.stats.max_decision_level = self.stats.max_decision_level.max(self.model.decision_level());
+						self.assign(lit, None);
+						if let Some(ref mut observer) = self.observer {
+							observer.on_decision(lit, self.model.decision_level());
+						}
+					},
 					//return SAT
-					//if length is 0, the CNF is sat
-					if self.len == 0 {
-						break;
+					//every variable is assigned without conflict
+					None => {
+						self.solution.clone_from(&self.model.var);
+						self.extend_model();
+						break SolveResult::Sat;
+					},
+				}
+			}
+		};
+		self.assumptions.clear();
+		self.stats.search_time += search_time.elapsed();
+		self.stats.solve_time += start_time.elapsed();
+		result
+	}
+	
+	///Return the assumptions responsible for the last UNSAT result of solve_with_assumptions.
+	///The CNF is UNSAT when all of them are assumed. Empty if the CNF is UNSAT without any assumption.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let mut solver = Solver::new();
+	///let x = Lit::create_lits(&solver.create_vars(3));
+	///
+	///solver.add_clause_from_lits(vec![!x[0], !x[1]]).unwrap();
+	///
+	///solver.solve_with_assumptions(&[x[0], x[2], x[1]]);
+	///for lit in solver.get_failed_assumptions() {
+	///    print!("{} ", lit); //1 0
+	///}
+	///```
+	pub fn get_failed_assumptions(&self) -> &[Lit] {
+		&self.failed
+	}
+	
+	///Check if an assumption is responsible for the last UNSAT result of solve_with_assumptions
+	pub fn is_failed(&self, lit: Lit) -> bool {
+		self.failed.contains(&lit)
+	}
+	
+	///Return the indices in get_oringin_clauses of original clauses which are UNSAT by themselves,
+	///after the last solve or add_clause found the CNF UNSAT. The core is not necessarily minimal.
+	///If the last solve is UNSAT under assumptions, the core is UNSAT together with the failed assumptions.
+	///
+	///SolverConfig::core_tracking must be enabled before the clauses are learnt, otherwise None is returned.
+	///None is also returned if a clause of the core is no longer original.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let config = SolverConfig { core_tracking: true, ..Default::default() };
+	///let mut solver = Solver::with_config(config);
+	///let x = Lit::create_lits(&solver.create_vars(3));
+	///
+	///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+	///solver.add_clause_from_lits(vec![x[2]]).unwrap();
+	///solver.add_clause_from_lits(vec![!x[0]]).unwrap();
+	///solver.add_clause_from_lits(vec![!x[1]]).unwrap();
+	///
+	///println!("{}", solver.solve()); //UNSAT
+	///println!("{:?}", solver.get_unsat_core()); //Some([0, 2, 3])
+	///```
+	pub fn get_unsat_core(&self) -> Option<Vec<usize>> {
+		let core = self.core.as_ref()?;
+		//translate the references of the CNF into indices of the original clauses, which are in the
+		//order of their references. A part of the core is not a core, so a stale reference gives None.
+		let origin: Vec<CRef> = self.cnf.refs().into_iter().filter(|&ci| !self.cnf.is_learnt(ci)).collect();
+		core.iter().map(|ci| origin.binary_search(ci).ok()).collect()
+	}
+	
+	///Shrink the UNSAT core to a minimal unsatisfiable subset (MUS), where removing any clause makes
+	///the rest satisfiable. Every clause of the core is tried to be removed, so it may take many solves.
+	///Return the indices in get_oringin_clauses, or None if get_unsat_core returns None.
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let config = SolverConfig { core_tracking: true, ..Default::default() };
+	///let mut solver = Solver::with_config(config);
+	///let x = Lit::create_lits(&solver.create_vars(2));
+	///
+	///solver.add_clause_from_lits(vec![x[0]]).unwrap();
+	///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+	///solver.add_clause_from_lits(vec![!x[0], x[1]]).unwrap();
+	///solver.add_clause_from_lits(vec![!x[1]]).unwrap();
+	///
+	///println!("{}", solver.solve()); //UNSAT
+	///println!("{:?}", solver.get_mus()); //Some([0, 2, 3])
+	///```
+	pub fn get_mus(&self) -> Option<Vec<usize>> {
+		let core = self.get_unsat_core()?;
+		let clauses = self.get_oringin_clauses();
+		
+		//clause i of the core is enabled by assuming the selector s[i]
+		let mut solver = Solver::new();
+		solver.create_vars(self.num_var);
+		for &lit in &self.failed {
+			solver.add_clause_from_lits(vec![lit]).unwrap();
+		}
+		let selectors = Lit::create_lits(&solver.create_vars(core.len()));
+		for (i, &ci) in core.iter().enumerate() {
+			let mut clause = clauses[ci].clone();
+			clause.push(!selectors[i]);
+			solver.add_clause(clause).unwrap();
+		}
+		
+		//deletion based: drop a clause if the rest is still UNSAT
+		let mut kept: Vec<bool> = vec![true; core.len()];
+		let mut needed: Vec<bool> = vec![false; core.len()];
+		for i in 0..core.len() {
+			if !kept[i] || needed[i] {
+				continue;
+			}
+			let assumptions: Vec<Lit> = (0..core.len()).filter(|&j| j != i && kept[j]).map(|j| selectors[j]).collect();
+			if solver.solve_with_assumptions(&assumptions) == SolveResult::Unsat {
+				//the clauses outside the failed assumptions are not needed either
+				kept[i] = false;
+				for j in 0..core.len() {
+					if kept[j] && !solver.is_failed(selectors[j]) {
+						kept[j] = false;
 					}
-					next_lit = None;
 				}
+			}else {
+				needed[i] = true;
 			}
-			if self.iter_num != 0 {
-				println!("\nTotal iteration: {}", cnt);
+		}
+		Some((0..core.len()).filter(|&i| kept[i]).map(|i| core[i]).collect())
+	}
+	
+	//find the assumptions which imply the negation of the given assumption
+	fn analyze_final(&mut self, lit: Lit) {
+		self.failed.clear();
+		self.failed.push(lit);
+		let var = lit.var_num();
+		let mut reasons = Vec::<CRef>::new();
+		if self.model.level[var] == 0 {
+			if self.config.core_tracking {
+				self.trace_core(self.model.reason[var].into_iter().collect());
 			}
+			return;
+		}
+		
+		self.model.seen[var] = true;
+		for i in (self.model.trail_lim[0]..self.model.trail.len()).rev() {
+			let p = self.model.trail[i];
+			let var = p.var_num();
+			if self.model.seen[var] {
+				match self.model.reason[var] {
+					//every decision on the trail is an assumption
+					None => self.failed.push(p),
+					Some(ci) => {
+						reasons.push(ci);
+						for &l in self.cnf.lits(ci) {
+							if self.model.level[l.var_num()] > 0 {
+								self.model.seen[l.var_num()] = true;
+							}
+						}
+					},
+				}
+				self.model.seen[var] = false;
+			}
+		}
+		self.model.seen[lit.var_num()] = false;
+		if self.config.core_tracking {
+			self.trace_core(reasons);
 		}
-		self.status
 	}
 	
-	///Reset the solver to the state before solving and simplifying
+	///Reset the solver to the state before solving and simplifying. The learnt clauses, the model,
+	///the core and the statistics are discarded. The configuration, the budget, the observer, the proof
+	///and the interrupt handles are kept.
+	///It is not needed before adding more clauses after solve.
 	pub fn reset(&mut self) {
-		for i in 0..self.model.len() {
-			self.model.propagated[i] = false;
-			self.model.expected[i] = (VUndef, 0);
-			self.model.var[i] = VUndef;
-		}
-		for i in 0..self.cnf.len() {
-			self.cnf.sat[i] = 0;
-			self.cnf.clauses[i].restore_all();
-			if self.cnf.clauses[i].len() == 1 {
-				let lit = self.cnf.clauses[i].get_first().unwrap();
-				self.model.var[lit.var_num()] = lit.get_value();
+		let clauses = self.get_oringin_clauses();
+		let num_var = self.num_var;
+		let config = self.config.clone();
+		let observer = self.observer.take();
+		let proof = self.proof.take();
+		let budget = self.budget;
+		//the handles created before share the flag
+		let interrupt = Arc::clone(&self.interrupt);
+		
+		*self = Solver::with_config(config);
+		self.observer = observer;
+		self.proof = proof;
+		self.budget = budget;
+		self.interrupt = interrupt;
+		self.create_vars(num_var);
+		for clause in clauses {
+			if self.add_clause(clause).is_err() {
+				break;
 			}
 		}
 	}
@@ -1104,15 +2128,24 @@ impl Solver {
 impl fmt::Display for Solver {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		let mut first = true;
-		for (i, c) in self.cnf.clauses.iter().enumerate() {
-			if self.cnf.sat[i] == 0 {
-				if !first {
-					write!(f, "/\\").unwrap();
+		for ci in self.cnf.refs() {
+			let lits = self.cnf.lits(ci);
+			//skip the clauses which are already satisfied
+			if self.cnf.is_learnt(ci) || lits.iter().any(|&lit| self.model.lit_value(lit) == VTrue) {
+				continue;
+			}
+			if !first {
+				write!(f, "/\\").unwrap();
+			}
+			first = false;
+			let mut clause = Clause::new();
+			for &lit in lits {
+				if self.model.lit_value(lit) == VUndef {
+					clause.push(lit);
 				}
-				first = false;
-				write!(f, "{}", c).unwrap();
 			}
+			write!(f, "{}", clause).unwrap();
 		}
 		write!(f, "")
 	}
-}
\ No newline at end of file
+}
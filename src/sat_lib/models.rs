@@ -0,0 +1,86 @@
+use sat_lib::{Solver, SolveResult, Var, VarValue, Lit};
+
+#[derive (Debug)]
+///Iterator over the models of a solver, created by Solver::models. After each model, a clause
+///blocking its values on the projection variables is added, so the next model differs on them.
+///
+///The blocking clauses are guarded by a new variable. When the iterator is dropped, the variable is
+///removed together with the blocking clauses and the clauses learnt from them, so the solver has the
+///same variables, original clauses and models afterwards.
+pub struct Models<'a> {
+	solver: 	&'a mut Solver,
+	projection: Vec<Var>,		//variables the models are blocked on
+	num_var: 	usize,			//number of variables before the guard is created
+	guard: 		Lit,			//the blocking clauses are enabled by assuming it
+	limit: 		Option<usize>,	//maximum number of models
+	count: 		usize,			//number of models found
+	result: 	Option<SolveResult>,	//result of the last solve
+}
+
+impl<'a> Models<'a> {
+	pub(super) fn new(solver: &'a mut Solver, projection: Option<&[Var]>, limit: Option<usize>) -> Self {
+		let num_var = solver.num_var;
+		let projection = match projection {
+			Some(vars) => vars.to_vec(),
+			None => (0..num_var).map(Var::new).collect(),
+		};
+		let guard = Lit::new(solver.new_var());
+		Models {
+			solver,
+			projection,
+			num_var,
+			guard,
+			limit,
+			count: 		0,
+			result: 	None,
+		}
+	}
+	
+	///Return the result of the last solve. UNSAT means every model is found, while Unknown or
+	///Interrupted means the enumeration is stopped by the budget or an interrupt.
+	pub fn get_result(&self) -> Option<SolveResult> {
+		self.result
+	}
+	
+	///Return the number of models found
+	pub fn get_count(&self) -> usize {
+		self.count
+	}
+}
+
+impl<'a> Iterator for Models<'a> {
+	type Item = Vec<VarValue>;
+	
+	fn next(&mut self) -> Option<Vec<VarValue>> {
+		if self.limit.is_some_and(|n| self.count >= n) || self.result.is_some_and(|r| r != SolveResult::Sat) {
+			return None;
+		}
+		let result = self.solver.solve_with_assumptions(&[self.guard]);
+		self.result = Some(result);
+		if result != SolveResult::Sat {
+			return None;
+		}
+		self.count += 1;
+		let model = self.solver.get_model()[..self.num_var].to_vec();
+		
+		//block the model on the projection variables
+		let mut lits = vec![!self.guard];
+		for var in &self.projection {
+			let lit = Lit::new(*var);
+			lits.push(if model[var.get_num()] == VarValue::VTrue {!lit} else {lit});
+		}
+		self.solver.add_clause_from_lits(lits).unwrap_or(false);
+		Some(model)
+	}
+}
+
+impl<'a> Drop for Models<'a> {
+	fn drop(&mut self) {
+		//remove the blocking clauses, the clauses learnt from them contain the guard too
+		self.solver.remove_last_var();
+		//the core of the last UNSAT result depends on the guard, unless the CNF is UNSAT without it
+		if self.solver.status {
+			self.solver.core = None;
+		}
+	}
+}
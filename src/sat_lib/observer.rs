@@ -0,0 +1,52 @@
+use std::fmt;
+use sat_lib::Lit;
+use sat_lib::stats::SolverStats;
+
+///Callbacks of the search, registered with Solver::set_observer. Every method does nothing by default,
+///so an observer only implements the events it needs.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///struct Logger;
+///
+///impl SolverObserver for Logger {
+///    fn on_restart(&mut self, stats: &SolverStats) {
+///        println!("restart after {} conflicts", stats.conflicts);
+///    }
+///}
+///
+///let mut solver = Solver::new();
+///let x = Lit::create_lits(&solver.create_vars(2));
+///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+///solver.set_observer(Box::new(Logger));
+///println!("{}", solver.solve()); //SAT
+///```
+pub trait SolverObserver {
+	///Called after a literal is decided, with the new decision level.
+	fn on_decision(&mut self, _lit: Lit, _level: usize) {}
+	
+	///Called when a conflict is found, with the decision level it is found on.
+	fn on_conflict(&mut self, _level: usize) {}
+	
+	///Called after a clause is learnt from a conflict, with its LBD.
+	fn on_learnt(&mut self, _clause: &[Lit], _lbd: usize) {}
+	
+	///Called after every restart.
+	fn on_restart(&mut self, _stats: &SolverStats) {}
+	
+	///Called every SolverConfig::progress_interval conflicts.
+	fn on_progress(&mut self, _stats: &SolverStats) {}
+	
+	///Checked before every decision. If it returns true, solve stops and returns SolveResult::Interrupted.
+	fn should_stop(&mut self) -> bool {
+		false
+	}
+}
+
+impl fmt::Debug for dyn SolverObserver + Send {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "SolverObserver")
+	}
+}
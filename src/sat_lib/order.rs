@@ -0,0 +1,132 @@
+use std::cmp::Ordering;
+
+#[derive (Debug)]
+//Activity based order of variables, used by VSIDS to pick the next decision variable.
+//Variables are kept in a binary max heap keyed by their activities.
+pub struct VarOrder {
+	activity: 	Vec<f64>,			//activity of each variable
+	heap: 		Vec<usize>,			//binary heap of variables
+	indices: 	Vec<Option<usize>>,	//position of each variable in the heap
+	var_inc: 	f64,				//amount to bump a variable with
+}
+
+impl Default for VarOrder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl VarOrder {
+	pub fn new() -> Self {
+		VarOrder {
+			activity: 	Vec::<f64>::new(),
+			heap: 		Vec::<usize>::new(),
+			indices: 	Vec::<Option<usize>>::new(),
+			var_inc: 	1.,
+		}
+	}
+	
+	//add a new variable with zero activity
+	pub fn new_var(&mut self) {
+		let var = self.activity.len();
+		self.activity.push(0.);
+		self.indices.push(None);
+		self.insert(var);
+	}
+	
+	//remove the variable added last, together with its activity
+	pub fn remove_last_var(&mut self) {
+		let var = self.activity.len() - 1;
+		//move it to the top of the heap, from which it is popped
+		if let Some(idx) = self.indices[var] {
+			self.activity[var] = f64::INFINITY;
+			self.percolate_up(idx);
+			self.pop();
+		}
+		self.activity.pop();
+		self.indices.pop();
+	}
+	
+	//insert a variable into the heap if it is not there
+	pub fn insert(&mut self, var: usize) {
+		if self.indices[var].is_none() {
+			self.indices[var] = Some(self.heap.len());
+			self.heap.push(var);
+			let idx = self.heap.len() - 1;
+			self.percolate_up(idx);
+		}
+	}
+	
+	//remove and return the variable with the highest activity
+	pub fn pop(&mut self) -> Option<usize> {
+		if self.heap.is_empty() {
+			return None;
+		}
+		let var = self.heap.swap_remove(0);
+		self.indices[var] = None;
+		if !self.heap.is_empty() {
+			self.indices[self.heap[0]] = Some(0);
+			self.percolate_down(0);
+		}
+		Some(var)
+	}
+	
+	//increase the activity of a variable
+	pub fn bump(&mut self, var: usize) {
+		self.activity[var] += self.var_inc;
+		//rescale all activities to avoid overflow
+		if self.activity[var] > 1e100 {
+			for act in self.activity.iter_mut() {
+				*act *= 1e-100;
+			}
+			self.var_inc *= 1e-100;
+		}
+		if let Some(idx) = self.indices[var] {
+			self.percolate_up(idx);
+		}
+	}
+	
+	//make the future bumps more important than the past ones
+	pub fn decay(&mut self, var_decay: f64) {
+		self.var_inc /= var_decay;
+	}
+	
+	fn less(&self, a: usize, b: usize) -> bool {
+		self.activity[a].partial_cmp(&self.activity[b]) == Some(Ordering::Less)
+	}
+	
+	fn percolate_up(&mut self, mut idx: usize) {
+		let var = self.heap[idx];
+		while idx > 0 {
+			let parent = (idx - 1) / 2;
+			if !self.less(self.heap[parent], var) {
+				break;
+			}
+			self.heap[idx] = self.heap[parent];
+			self.indices[self.heap[idx]] = Some(idx);
+			idx = parent;
+		}
+		self.heap[idx] = var;
+		self.indices[var] = Some(idx);
+	}
+	
+	fn percolate_down(&mut self, mut idx: usize) {
+		let var = self.heap[idx];
+		loop {
+			let left = idx * 2 + 1;
+			if left >= self.heap.len() {
+				break;
+			}
+			let right = left + 1;
+			let child = if right < self.heap.len() && self.less(self.heap[left], self.heap[right]) {right} else {left};
+			if !self.less(var, self.heap[child]) {
+				break;
+			}
+			self.heap[idx] = self.heap[child];
+			self.indices[self.heap[idx]] = Some(idx);
+			idx = child;
+		}
+		self.heap[idx] = var;
+		self.indices[var] = Some(idx);
+	}
+}
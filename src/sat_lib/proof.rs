@@ -0,0 +1,617 @@
+use std::collections::HashMap;
+use std::io::{BufRead, Read, Write};
+use std::mem;
+use sat_lib::{Clause, Lit, VarValue, ProofFormat, read_dimacs, write_dimacs};
+use sat_lib::VarValue::*;
+
+#[derive (Debug, Clone)]
+///Result of checking a DRAT proof.
+pub enum ProofResult {
+	///Every lemma needed to derive the empty clause is RUP or RAT, so the CNF is UNSAT.
+	Verified,
+	///A lemma needed to derive the empty clause is neither RUP nor RAT.
+	///`step` counts the additions and deletions of the proof from 1, which is the line
+	///of the lemma in a text proof without comments. The empty clause implied by the end
+	///of the proof is one step after the last.
+	Failed {
+		step: 	usize,
+		lemma: 	Clause,
+	},
+	///The proof does not derive the empty clause.
+	Incomplete,
+}
+
+#[derive (Debug, Copy, Clone)]
+//A clause added or deleted by the proof
+enum Step {
+	Add(usize, usize),					//the clause and the step of the proof adding it
+	Delete(usize),
+}
+
+#[derive (Debug)]
+///Checker of DRAT proofs. The lemmas are checked backward from the empty clause, and only
+///the lemmas used to derive it are checked, so the original clauses and lemmas they use
+///form an UNSAT core.
+///
+///Deletions of unit clauses are ignored, as in drat-trim.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///let mut solver = Solver::new();
+///let x = Lit::create_lits(&solver.create_vars(2));
+///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+///solver.add_clause_from_lits(vec![x[0], !x[1]]).unwrap();
+///solver.add_clause_from_lits(vec![!x[0], x[1]]).unwrap();
+///solver.add_clause_from_lits(vec![!x[0], !x[1]]).unwrap();
+///
+///let mut checker = ProofChecker::new(&solver.get_oringin_clauses());
+///let proof = "1 0\n0\n";
+///match checker.check(proof.as_bytes(), ProofFormat::Text).unwrap() {
+///    ProofResult::Verified => println!("{}", checker.get_core().len()), //4
+///    _ => println!("wrong proof"),
+///}
+///```
+pub struct ProofChecker {
+	clauses: 	Vec<Vec<Lit>>,			//original clauses followed by the lemmas
+	num_orig: 	usize,					//number of original clauses
+	pivot: 		Vec<Option<Lit>>,		//first literal of each clause, the pivot of RAT
+	active: 	Vec<bool>,				//if the clause is in the CNF at the current step
+	marked: 	Vec<bool>,				//if the clause is used to derive the empty clause
+	hints: 		Vec<Vec<i64>>,			//LRAT hints of each checked lemma
+	steps: 		Vec<Step>,				//additions and deletions of the proof
+	units: 		Vec<usize>,				//unit clauses
+	watches: 	Vec<Vec<usize>>,		//clauses watching each literal
+	value: 		Vec<VarValue>,			//current assignment of each variable
+	reason: 	Vec<Option<usize>>,		//clause implying each variable
+	position: 	Vec<usize>,				//position of each variable in the trail
+	seen: 		Vec<bool>,				//marks of variables used in analysis
+	trail: 		Vec<Lit>,
+	qhead: 		usize,
+	verified: 	bool,
+}
+
+impl ProofChecker {
+	///Create a checker of proofs for the given CNF
+	pub fn new(clauses: &[Clause]) -> Self {
+		let mut checker = ProofChecker {
+			clauses: 	Vec::<Vec<Lit>>::new(),
+			num_orig: 	clauses.len(),
+			pivot: 		Vec::<Option<Lit>>::new(),
+			active: 	Vec::<bool>::new(),
+			marked: 	Vec::<bool>::new(),
+			hints: 		Vec::<Vec<i64>>::new(),
+			steps: 		Vec::<Step>::new(),
+			units: 		Vec::<usize>::new(),
+			watches: 	Vec::<Vec<usize>>::new(),
+			value: 		Vec::<VarValue>::new(),
+			reason: 	Vec::<Option<usize>>::new(),
+			position: 	Vec::<usize>::new(),
+			seen: 		Vec::<bool>::new(),
+			trail: 		Vec::<Lit>::new(),
+			qhead: 		0,
+			verified: 	false,
+		};
+		for clause in clauses {
+			checker.add(&clause.vec_lit);
+		}
+		checker
+	}
+	
+	///Create a checker of proofs for a CNF in DIMACS format
+	///
+	///# Examples
+	///
+	///```
+	///# use sat::sat_lib::*;
+	///let cnf = "p cnf 1 2\n1 0\n-1 0\n";
+	///let mut checker = ProofChecker::from_dimacs(cnf.as_bytes()).unwrap();
+	///
+	///let res = checker.check("0\n".as_bytes(), ProofFormat::Text).unwrap();
+	///println!("{:?}", res); //Verified
+	///```
+	pub fn from_dimacs<R: BufRead>(reader: R) -> Result<Self, String> {
+		Ok(ProofChecker::new(&read_dimacs(reader)?))
+	}
+	
+	///Check a DRAT proof of the CNF. Return an error if the proof cannot be read.
+	///A checker can only check one proof.
+	pub fn check<R: Read>(&mut self, mut proof: R, format: ProofFormat) -> Result<ProofResult, String> {
+		if self.clauses.len() > self.num_orig {
+			return Err("A proof has been checked".to_string());
+		}
+		let mut bytes = Vec::<u8>::new();
+		proof.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+		let lines = match format {
+			ProofFormat::Text => parse_text(&bytes)?,
+			ProofFormat::Binary => parse_binary(&bytes)?,
+		};
+		
+		//the empty clause may be in the CNF already
+		let mut empty = (0..self.num_orig).find(|&ci| self.clauses[ci].is_empty());
+		
+		//forward pass, which finds the empty clause and the clauses each deletion refers to
+		let mut ids = HashMap::<Vec<Lit>, Vec<usize>>::new();
+		for ci in 0..self.num_orig {
+			ids.entry(sorted(&self.clauses[ci])).or_default().push(ci);
+		}
+		let num_steps = lines.len();
+		for (step, (delete, lits)) in lines.into_iter().enumerate() {
+			if empty.is_some() {
+				break;
+			}
+			if delete {
+				if let Some(ci) = ids.get_mut(&sorted(&lits)).and_then(|v| v.pop()) {
+					if self.clauses[ci].len() == 1 {
+						ids.get_mut(&sorted(&lits)).unwrap().push(ci);
+					}else {
+						self.active[ci] = false;
+						self.steps.push(Step::Delete(ci));
+					}
+				}
+			}else {
+				let ci = self.add(&lits);
+				ids.entry(sorted(&lits)).or_default().push(ci);
+				self.steps.push(Step::Add(ci, step + 1));
+				if lits.is_empty() {
+					empty = Some(ci);
+				}
+			}
+		}
+		//the proof may end with a conflict of unit propagation instead of the empty clause
+		if empty.is_none() && self.propagate_units().is_some() {
+			let ci = self.add(&[]);
+			self.steps.push(Step::Add(ci, num_steps + 1));
+			empty = Some(ci);
+		}
+		let empty = match empty {
+			Some(ci) => ci,
+			None => return Ok(ProofResult::Incomplete),
+		};
+		
+		//backward pass, which checks the lemmas used to derive the empty clause
+		self.marked[empty] = true;
+		for i in (0..self.steps.len()).rev() {
+			match self.steps[i] {
+				Step::Add(ci, step) => {
+					self.active[ci] = false;
+					if self.marked[ci] && !self.check_lemma(ci) {
+						let mut lemma = Clause::new();
+						for &lit in &self.clauses[ci] {
+							lemma.push(lit);
+						}
+						return Ok(ProofResult::Failed { step, lemma });
+					}
+				},
+				Step::Delete(ci) => self.active[ci] = true,
+			}
+		}
+		self.verified = true;
+		Ok(ProofResult::Verified)
+	}
+	
+	///Return the original clauses used to derive the empty clause, after a proof is verified
+	pub fn get_core(&self) -> Vec<Clause> {
+		let mut core = Vec::<Clause>::new();
+		if self.verified {
+			for ci in 0..self.num_orig {
+				if self.marked[ci] {
+					let mut clause = Clause::new();
+					for &lit in &self.clauses[ci] {
+						clause.push(lit);
+					}
+					core.push(clause);
+				}
+			}
+		}
+		core
+	}
+	
+	///Write the UNSAT core of a verified proof in DIMACS format
+	pub fn write_core<W: Write>(&self, writer: W) -> Result<(), String> {
+		if !self.verified {
+			return Err("The proof is not verified".to_string());
+		}
+		write_dimacs(writer, &self.get_core())
+	}
+	
+	///Write the lemmas of a verified proof used to derive the empty clause in LRAT format.
+	///The original clauses are numbered from 1 in the order of the CNF.
+	pub fn write_lrat<W: Write>(&self, mut writer: W) -> Result<(), String> {
+		if !self.verified {
+			return Err("The proof is not verified".to_string());
+		}
+		for step in &self.steps {
+			if let Step::Add(ci, _) = *step {
+				if self.marked[ci] {
+					write!(writer, "{} ", ci + 1).map_err(|e| e.to_string())?;
+					for lit in self.lemma_order(ci) {
+						write!(writer, "{} ", lit.to_dimacs()).map_err(|e| e.to_string())?;
+					}
+					write!(writer, "0 ").map_err(|e| e.to_string())?;
+					for hint in &self.hints[ci] {
+						write!(writer, "{} ", hint).map_err(|e| e.to_string())?;
+					}
+					writeln!(writer, "0").map_err(|e| e.to_string())?;
+				}
+			}
+		}
+		Ok(())
+	}
+	
+	//add a clause without duplicate literals, and watch its first two literals
+	fn add(&mut self, lits: &[Lit]) -> usize {
+		let ci = self.clauses.len();
+		let mut clause = Vec::<Lit>::new();
+		for &lit in lits {
+			while lit.var_num() >= self.value.len() {
+				self.new_var();
+			}
+			if !clause.contains(&lit) {
+				clause.push(lit);
+			}
+		}
+		match clause.len() {
+			0 => {},
+			1 => self.units.push(ci),
+			_ => {
+				self.watches[clause[0].index()].push(ci);
+				self.watches[clause[1].index()].push(ci);
+			},
+		}
+		self.pivot.push(clause.first().cloned());
+		self.clauses.push(clause);
+		self.active.push(true);
+		self.marked.push(false);
+		self.hints.push(Vec::<i64>::new());
+		ci
+	}
+	
+	fn new_var(&mut self) {
+		self.watches.push(Vec::<usize>::new());
+		self.watches.push(Vec::<usize>::new());
+		self.value.push(VUndef);
+		self.reason.push(None);
+		self.position.push(0);
+		self.seen.push(false);
+	}
+	
+	//literals of a lemma with its pivot first
+	fn lemma_order(&self, ci: usize) -> Vec<Lit> {
+		let mut lits = self.clauses[ci].clone();
+		if let Some(pivot) = self.pivot[ci] {
+			if let Some(i) = lits.iter().position(|&l| l == pivot) {
+				lits.swap(0, i);
+			}
+		}
+		lits
+	}
+	
+	//check if the lemma is RUP or RAT to the active clauses, and mark the clauses used
+	fn check_lemma(&mut self, ci: usize) -> bool {
+		let lemma = self.clauses[ci].clone();
+		if lemma.iter().any(|&l| lemma.contains(&!l)) {
+			return true;
+		}
+		if let Some(confl) = self.propagate_negation(&lemma) {
+			self.hints[ci] = self.analyze(confl);
+			return true;
+		}
+		//RAT on the pivot: every resolvent with a clause containing the negated pivot is RUP
+		let pivot = match self.pivot[ci] {
+			Some(pivot) => pivot,
+			None => return false,
+		};
+		let mut hints = Vec::<i64>::new();
+		for di in 0..ci {
+			if !self.active[di] || !self.clauses[di].contains(&!pivot) {
+				continue;
+			}
+			let mut resolvent = lemma.clone();
+			for &lit in &self.clauses[di] {
+				if lit != !pivot && !resolvent.contains(&lit) {
+					resolvent.push(lit);
+				}
+			}
+			hints.push(-(di as i64 + 1));
+			self.marked[di] = true;
+			match self.propagate_negation(&resolvent) {
+				Some(confl) => hints.extend(self.analyze(confl)),
+				None if resolvent.iter().any(|&l| resolvent.contains(&!l)) => {},
+				None => return false,
+			}
+		}
+		self.hints[ci] = hints;
+		true
+	}
+	
+	//assign the negation of the literals, then propagate the active clauses
+	//return the conflict clause if there is one
+	fn propagate_negation(&mut self, lits: &[Lit]) -> Option<usize> {
+		self.clear();
+		for &lit in lits {
+			match self.lit_value(lit) {
+				VUndef => self.assign(!lit, None),
+				//the literals form a tautology
+				VTrue => return None,
+				VFalse => {},
+			}
+		}
+		self.propagate_units()
+	}
+	
+	//assign the active unit clauses, then propagate the active clauses
+	fn propagate_units(&mut self) -> Option<usize> {
+		for i in 0..self.units.len() {
+			let ci = self.units[i];
+			if !self.active[ci] {
+				continue;
+			}
+			let lit = self.clauses[ci][0];
+			match self.lit_value(lit) {
+				VUndef => self.assign(lit, Some(ci)),
+				VFalse => return Some(ci),
+				VTrue => {},
+			}
+		}
+		self.propagate()
+	}
+	
+	fn propagate(&mut self) -> Option<usize> {
+		while self.qhead < self.trail.len() {
+			let false_lit = !self.trail[self.qhead];
+			self.qhead += 1;
+			let mut ws = mem::take(&mut self.watches[false_lit.index()]);
+			let mut confl = None;
+			let mut i = 0;
+			let mut j = 0;
+			'next_clause: while i < ws.len() {
+				let ci = ws[i];
+				i += 1;
+				if !self.active[ci] || confl.is_some() {
+					ws[j] = ci;
+					j += 1;
+					continue;
+				}
+				if self.clauses[ci][0] == false_lit {
+					self.clauses[ci].swap(0, 1);
+				}
+				let first = self.clauses[ci][0];
+				if self.lit_value(first) != VTrue {
+					for k in 2..self.clauses[ci].len() {
+						let lit = self.clauses[ci][k];
+						if self.lit_value(lit) != VFalse {
+							self.clauses[ci].swap(1, k);
+							self.watches[lit.index()].push(ci);
+							continue 'next_clause;
+						}
+					}
+					if self.lit_value(first) == VFalse {
+						confl = Some(ci);
+					}else {
+						self.assign(first, Some(ci));
+					}
+				}
+				ws[j] = ci;
+				j += 1;
+			}
+			ws.truncate(j);
+			self.watches[false_lit.index()] = ws;
+			if confl.is_some() {
+				return confl;
+			}
+		}
+		None
+	}
+	
+	//mark the clauses which imply the conflict, and return their LRAT numbers in the order of
+	//unit propagation, ending with the conflict clause
+	fn analyze(&mut self, confl: usize) -> Vec<i64> {
+		let mut used = vec![confl];
+		let mut stack = self.clauses[confl].clone();
+		while let Some(lit) = stack.pop() {
+			let var = lit.var_num();
+			if self.seen[var] {
+				continue;
+			}
+			self.seen[var] = true;
+			if let Some(ci) = self.reason[var] {
+				used.push(ci);
+				stack.extend(self.clauses[ci].iter().cloned().filter(|l| l.var_num() != var));
+			}
+		}
+		for &ci in &used {
+			self.marked[ci] = true;
+		}
+		let mut reasons = used.split_off(1);
+		reasons.sort_by_key(|&ci| self.position[self.clauses[ci][0].var_num()]);
+		reasons.dedup();
+		reasons.push(confl);
+		reasons.into_iter().map(|ci| ci as i64 + 1).collect()
+	}
+	
+	fn lit_value(&self, lit: Lit) -> VarValue {
+		let value = self.value[lit.var_num()];
+		if value == VUndef {
+			VUndef
+		}else if value == lit.get_value() {
+			VTrue
+		}else {
+			VFalse
+		}
+	}
+	
+	fn assign(&mut self, lit: Lit, reason: Option<usize>) {
+		let var = lit.var_num();
+		self.value[var] = lit.get_value();
+		self.reason[var] = reason;
+		self.position[var] = self.trail.len();
+		self.trail.push(lit);
+	}
+	
+	//undo every assignment
+	fn clear(&mut self) {
+		for lit in self.trail.drain(..) {
+			let var = lit.var_num();
+			self.value[var] = VUndef;
+			self.reason[var] = None;
+			self.seen[var] = false;
+		}
+		self.qhead = 0;
+	}
+}
+
+//literals of a clause in a canonical order, used to find the clause a deletion refers to
+fn sorted(lits: &[Lit]) -> Vec<Lit> {
+	let mut lits = lits.to_vec();
+	lits.sort_by_key(|l| l.index());
+	lits.dedup();
+	lits
+}
+
+//parse a text proof into additions and deletions
+fn parse_text(bytes: &[u8]) -> Result<Vec<(bool, Vec<Lit>)>, String> {
+	let text = String::from_utf8_lossy(bytes);
+	let mut lines = Vec::<(bool, Vec<Lit>)>::new();
+	let mut delete = false;
+	let mut lits = Vec::<Lit>::new();
+	for line in text.lines() {
+		if line.trim_start().starts_with('c') {
+			continue;
+		}
+		for token in line.split_whitespace() {
+			if token == "d" {
+				delete = true;
+				continue;
+			}
+			let num = token.parse::<i64>().map_err(|_| format!("Invalid literal: {}", token))?;
+			match Lit::parse_dimacs(num)? {
+				Some(lit) => lits.push(lit),
+				None => {
+					lines.push((delete, mem::take(&mut lits)));
+					delete = false;
+				},
+			}
+		}
+	}
+	if !lits.is_empty() || delete {
+		return Err("The last clause is not terminated by 0".to_string());
+	}
+	Ok(lines)
+}
+
+//parse a binary proof into additions and deletions
+fn parse_binary(bytes: &[u8]) -> Result<Vec<(bool, Vec<Lit>)>, String> {
+	let mut lines = Vec::<(bool, Vec<Lit>)>::new();
+	let mut i = 0;
+	while i < bytes.len() {
+		let delete = match bytes[i] {
+			b'a' => false,
+			b'd' => true,
+			b => return Err(format!("Invalid byte: {:#x}", b)),
+		};
+		i += 1;
+		let mut lits = Vec::<Lit>::new();
+		loop {
+			let mut num = 0u64;
+			let mut shift = 0;
+			loop {
+				let byte = match bytes.get(i) {
+					Some(&byte) => byte,
+					None => return Err("The last clause is not terminated by 0".to_string()),
+				};
+				i += 1;
+				if shift >= 64 {
+					return Err("Invalid literal: more than 64 bits".to_string());
+				}
+				num |= ((byte & 0x7f) as u64) << shift;
+				shift += 7;
+				if byte & 0x80 == 0 {
+					break;
+				}
+			}
+			if num == 0 {
+				break;
+			}
+			//2 * var + sign, with variables counted from 1
+			let var = (num >> 1) as i64;
+			match Lit::parse_dimacs(if num & 1 == 1 {-var} else {var})? {
+				Some(lit) => lits.push(lit),
+				None => return Err(format!("Invalid literal: {:#x}", num)),
+			}
+		}
+		lines.push((delete, lits));
+	}
+	Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn check(cnf: &str, proof: &str) -> ProofResult {
+		let mut checker = ProofChecker::from_dimacs(cnf.as_bytes()).unwrap();
+		checker.check(proof.as_bytes(), ProofFormat::Text).unwrap()
+	}
+	
+	//every clause over 1, 2 and 3, so no unit is propagated without a decision
+	const CUBE: &str = "1 2 3 0\n1 2 -3 0\n1 -2 3 0\n1 -2 -3 0\n-1 2 3 0\n-1 2 -3 0\n-1 -2 3 0\n-1 -2 -3 0\n";
+	
+	#[test]
+	fn rat_lemma() {
+		//(6) is not RUP, but RAT on 6, and (2) is only RUP with (6)
+		let cnf = format!("{}-6 1 2 0\n-6 -1 2 0\n", CUBE);
+		let mut checker = ProofChecker::from_dimacs(cnf.as_bytes()).unwrap();
+		let res = checker.check("6 0\n2 0\n1 0\n0\n".as_bytes(), ProofFormat::Text).unwrap();
+		assert!(matches!(res, ProofResult::Verified));
+		assert_eq!(checker.get_core().len(), 10);
+		
+		let mut lrat = Vec::<u8>::new();
+		checker.write_lrat(&mut lrat).unwrap();
+		assert!(String::from_utf8(lrat).unwrap().starts_with("11 6 0 -9 "));
+		
+		//(6 4) is not RUP, so neither is the resolvent of (6) and (-6 4)
+		let cnf = format!("{}-6 1 2 0\n-6 -1 2 0\n-6 4 0\n", CUBE);
+		let mut checker = ProofChecker::from_dimacs(cnf.as_bytes()).unwrap();
+		let res = checker.check("6 0\n2 0\n1 0\n0\n".as_bytes(), ProofFormat::Text).unwrap();
+		assert!(matches!(res, ProofResult::Failed { step: 1, .. }));
+	}
+	
+	#[test]
+	fn wrong_lemma() {
+		let res = check("1 2 0\n", "-1 0\n0\n");
+		assert!(matches!(res, ProofResult::Failed { step: 2, .. }));
+		let res = check("1 2 0\n-1 2 0\n", "2 0\n");
+		assert!(matches!(res, ProofResult::Incomplete));
+	}
+	
+	#[test]
+	fn failed_step_after_unit_deletion() {
+		//the deletion of the unit (1) is ignored, but it is still a step of the proof
+		let res = check("1 0\n2 0\n", "d 1 0\nc comment\n-2 0\n0\n");
+		assert!(matches!(res, ProofResult::Failed { step: 2, .. }));
+	}
+	
+	#[test]
+	fn unreadable_binary() {
+		let mut checker = ProofChecker::from_dimacs("1 2 0\n".as_bytes()).unwrap();
+		//the literal 0x01 is variable 0
+		assert!(checker.check(&[b'a', 0x01, 0x00][..], ProofFormat::Binary).is_err());
+		let mut checker = ProofChecker::from_dimacs("1 2 0\n".as_bytes()).unwrap();
+		let mut proof = vec![b'a'];
+		proof.extend_from_slice(&[0xff; 20]);
+		proof.extend_from_slice(&[0x01, 0x00]);
+		assert!(checker.check(&proof[..], ProofFormat::Binary).is_err());
+		//the literal 2^40 is variable 2^39, out of range
+		let mut checker = ProofChecker::from_dimacs("1 2 0\n".as_bytes()).unwrap();
+		assert!(checker.check(&[b'a', 0x80, 0x80, 0x80, 0x80, 0x80, 0x20, 0x00][..], ProofFormat::Binary).is_err());
+	}
+	
+	#[test]
+	fn variable_out_of_range() {
+		assert!(ProofChecker::from_dimacs("1 -2147483649 0\n".as_bytes()).is_err());
+		let mut checker = ProofChecker::from_dimacs("1 2 0\n".as_bytes()).unwrap();
+		assert!(checker.check("4294967296 0\n".as_bytes(), ProofFormat::Text).is_err());
+	}
+}
@@ -0,0 +1,111 @@
+use std::collections::VecDeque;
+
+use sat_lib::config::Restart;
+
+#[derive (Debug)]
+//Decide when the search should restart, following the configured strategy
+pub struct RestartPolicy {
+	strategy: 	Restart,
+	restarts: 	usize,				//number of restarts performed so far
+	conflicts: 	usize,				//number of conflicts since the last restart
+	lbd_queue: 	VecDeque<usize>,	//LBD of the most recent learnt clauses
+	queue_sum: 	usize,				//sum of LBD in the queue
+	lbd_sum: 	usize,				//sum of LBD of all learnt clauses
+	lbd_num: 	usize,				//number of learnt clauses
+}
+
+impl RestartPolicy {
+	pub fn new(strategy: Restart) -> Self {
+		RestartPolicy {
+			strategy,
+			restarts: 	0,
+			conflicts: 	0,
+			lbd_queue: 	VecDeque::<usize>::new(),
+			queue_sum: 	0,
+			lbd_sum: 	0,
+			lbd_num: 	0,
+		}
+	}
+	
+	//record a conflict together with the LBD of the clause learnt from it
+	pub fn on_conflict(&mut self, lbd: usize) {
+		self.conflicts += 1;
+		self.lbd_sum += lbd;
+		self.lbd_num += 1;
+		if let Restart::Glucose { window, .. } = self.strategy {
+			self.lbd_queue.push_back(lbd);
+			self.queue_sum += lbd;
+			if self.lbd_queue.len() > window {
+				self.queue_sum -= self.lbd_queue.pop_front().unwrap();
+			}
+		}
+	}
+	
+	//check if the search should restart now
+	//at least one conflict is needed since the last restart, so a zero interval cannot stop the search
+	pub fn should_restart(&self) -> bool {
+		if self.conflicts == 0 {
+			return false;
+		}
+		match self.strategy {
+			Restart::Never => false,
+			Restart::Luby { unit } => self.conflicts as f64 >= unit as f64 * luby(2., self.restarts),
+			Restart::Geometric { first, factor } => self.conflicts as f64 >= first as f64 * factor.powi(self.restarts as i32),
+			Restart::Glucose { window, k } => {
+				//restart if the recent learnt clauses are worse than the average
+				self.lbd_queue.len() >= window &&
+					self.queue_sum as f64 / window as f64 * k > self.lbd_sum as f64 / self.lbd_num as f64
+			},
+		}
+	}
+	
+	//reset the counters after a restart
+	pub fn on_restart(&mut self) {
+		self.restarts += 1;
+		self.conflicts = 0;
+		self.lbd_queue.clear();
+		self.queue_sum = 0;
+	}
+}
+
+//the x-th element of the Luby sequence (1, 1, 2, 1, 1, 2, 4, ...) with base y
+fn luby(y: f64, mut x: usize) -> f64 {
+	//find the finite subsequence that contains x, and its size
+	let mut size = 1;
+	let mut seq = 0;
+	while size < x + 1 {
+		seq += 1;
+		size = 2 * size + 1;
+	}
+	while size - 1 != x {
+		size = (size - 1) >> 1;
+		seq -= 1;
+		x %= size;
+	}
+	y.powi(seq)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{luby, RestartPolicy};
+	use sat_lib::config::Restart;
+	
+	#[test]
+	fn luby_sequence() {
+		let seq: Vec<f64> = (0..15).map(|i| luby(2., i)).collect();
+		assert_eq!(seq, vec![1., 1., 2., 1., 1., 2., 4., 1., 1., 2., 1., 1., 2., 4., 8.]);
+	}
+	
+	#[test]
+	fn no_restart_without_conflict() {
+		for strategy in [Restart::Luby { unit: 0 }, Restart::Geometric { first: 0, factor: 1.5 }, Restart::Geometric { first: 1, factor: 0.5 }] {
+			let mut policy = RestartPolicy::new(strategy);
+			for _ in 0..5 {
+				assert!(!policy.should_restart());
+				policy.on_conflict(2);
+				assert!(policy.should_restart());
+				policy.on_restart();
+			}
+		}
+	}
+}
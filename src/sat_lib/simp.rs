@@ -0,0 +1,816 @@
+use std::mem;
+use sat_lib::{Solver, Clause, Lit};
+use sat_lib::arena::CRef;
+use sat_lib::VarValue::*;
+
+//probing stops after this many propagations
+const PROBE_PROPAGATION_LIMIT: u64 = 1_000_000;
+//a clause is not checked for being blocked on a literal whose negation is in more clauses
+const BLOCKED_PARTNER_LIMIT: usize = 100;
+//a variable is not eliminated if it would need more resolutions
+const ELIM_RESOLUTION_LIMIT: usize = 400;
+//a variable is not eliminated if a resolvent would be longer
+const ELIM_RESOLVENT_LIMIT: usize = 20;
+//each vivification stops after this many propagations
+const VIVIFY_PROPAGATION_LIMIT: u64 = 100_000;
+
+//Preprocessing of the original clauses, run by simplify when it is enabled in SolverConfig.
+//The passes which remove clauses push them on the reconstruction stack, which extends the model to
+//the removed variables, and from which the clauses are added back when a removed variable is used again.
+//Vivification is run by the search instead, between restarts.
+impl Solver {
+	//run the enabled passes if original clauses were added since they last ran
+	pub(super) fn preprocess(&mut self) {
+		if !self.modified || self.config.core_tracking {
+			return;
+		}
+		if self.config.subsumption {
+			self.subsume_clauses();
+		}
+		if self.status && self.config.probing {
+			self.probe_literals();
+		}
+		//the clauses removed would have to be justified when they are added back
+		if self.status && self.config.equivalence && self.proof.is_none() {
+			self.substitute_equivalences();
+		}
+		if self.status && self.config.blocked_clauses && self.proof.is_none() {
+			self.eliminate_blocked();
+		}
+		if self.status && self.config.elimination && self.proof.is_none() {
+			self.eliminate_vars();
+		}
+		self.modified = false;
+	}
+	
+	//give values to the eliminated variables, so that the model satisfies the removed clauses
+	pub(super) fn extend_model(&mut self) {
+		for var in 0..self.num_var {
+			if self.model.eliminated[var] {
+				self.solution[var] = VFalse;
+			}
+		}
+		for (witness, lits) in self.elim_stack.iter().rev() {
+			if !lits.iter().any(|l| self.solution[l.var_num()] == l.get_value()) {
+				self.solution[witness.var_num()] = witness.get_value();
+			}
+		}
+	}
+	
+	//add the removed clauses back, so that the eliminated variables can be used again
+	pub(super) fn restore_eliminated(&mut self) {
+		let stack = mem::take(&mut self.elim_stack);
+		for var in 0..self.num_var {
+			self.model.witness[var] = false;
+			if self.model.eliminated[var] {
+				self.model.eliminated[var] = false;
+				self.order.insert(var);
+			}
+		}
+		self.front_pt = 0;
+		for (_, lits) in stack {
+			let mut clause = Clause::new();
+			for lit in lits {
+				clause.push(lit);
+			}
+			if self.add_clause(clause).is_err() {
+				break;
+			}
+		}
+	}
+	
+	//equivalent literal substitution: the literals of a strongly connected component of the binary
+	//implication graph are equivalent, so each of them is replaced by one representative
+	fn substitute_equivalences(&mut self) {
+		//a binary clause (a \/ b) gives the implications ~a -> b and ~b -> a
+		let mut graph = vec![Vec::<usize>::new(); self.num_var * 2];
+		for ci in self.cnf.refs() {
+			if self.cnf.is_deleted(ci) || self.cnf.lits(ci).len() != 2 || self.is_satisfied(ci) {
+				continue;
+			}
+			let (a, b) = (self.cnf.lits(ci)[0], self.cnf.lits(ci)[1]);
+			if self.model.lit_value(a) == VUndef && self.model.lit_value(b) == VUndef {
+				graph[(!a).index()].push(b.index());
+				graph[(!b).index()].push(a.index());
+			}
+		}
+		let component = strongly_connected(&graph);
+		
+		//a literal equivalent to its negation makes the CNF UNSAT, and the unit is found by propagation
+		for var in 0..self.num_var {
+			if component[var * 2] == component[var * 2 + 1] {
+				self.add_derived(vec![Lit::create(var, false)]);
+				return;
+			}
+		}
+		//the representative of a component is the lowest variable, but the assumptions are kept
+		let frozen = self.frozen_vars();
+		let mut representative = vec![None; self.num_var * 2];
+		for &keep in &[true, false] {
+			for var in (0..self.num_var).filter(|&v| frozen[v] == keep) {
+				let lit = Lit::create(var, true);
+				if representative[component[lit.index()]].is_none() {
+					representative[component[lit.index()]] = Some(lit);
+					representative[component[(!lit).index()]] = Some(!lit);
+				}
+			}
+		}
+		let mut substitute = vec![None; self.num_var];
+		for var in 0..self.num_var {
+			let lit = Lit::create(var, true);
+			let rep = representative[component[lit.index()]].unwrap();
+			if rep != lit && !frozen[var] && self.model.var[var] == VUndef {
+				substitute[var] = Some((lit, rep));
+			}
+		}
+		if substitute.iter().all(|s| s.is_none()) {
+			return;
+		}
+		
+		//the substituted variables take the values of their representatives
+		//the equivalences are kept first, so that the clauses are still equivalent if UNSAT is found
+		for (var, s) in substitute.iter().enumerate() {
+			if let Some((lit, rep)) = *s {
+				self.elim_stack.push((lit, vec![lit, !rep]));
+				self.elim_stack.push((!lit, vec![!lit, rep]));
+				self.model.eliminated[var] = true;
+				self.model.witness[var] = true;
+				self.stats.substituted_vars += 1;
+			}
+		}
+		let replace = |lit: Lit| match substitute[lit.var_num()] {
+			Some((l, rep)) => if lit == l {rep} else {!rep},
+			None => lit,
+		};
+		//every clause is removed before the new ones are propagated, which must not assign a
+		//substituted variable
+		let mut replaced = Vec::<Vec<Lit>>::new();
+		for ci in self.cnf.refs() {
+			if self.cnf.is_deleted(ci) || !self.cnf.lits(ci).iter().any(|l| substitute[l.var_num()].is_some()) {
+				continue;
+			}
+			//the satisfied clauses are kept, and the learnt clauses are dropped, as after elimination
+			if !self.cnf.is_learnt(ci) && self.is_satisfied(ci) {
+				continue;
+			}
+			if !self.cnf.is_learnt(ci) {
+				let mut lits = Vec::<Lit>::new();
+				for &lit in self.cnf.lits(ci) {
+					let lit = replace(lit);
+					if !lits.contains(&lit) {
+						lits.push(lit);
+					}
+				}
+				//the clauses which become tautologies are implied by the equivalences
+				if !lits.iter().any(|&l| lits.contains(&!l)) {
+					replaced.push(lits);
+				}
+			}
+			self.remove_clause(ci, None);
+		}
+		for lits in replaced {
+			self.add_derived(lits);
+			if !self.status {
+				return;
+			}
+		}
+	}
+	
+	//remove the clauses of the pure literals and the blocked clauses, until none is left
+	//a clause is blocked on one of its literals if every resolvent on it is a tautology, and the
+	//model is repaired by making that literal true
+	fn eliminate_blocked(&mut self) {
+		let occurs = self.occurrence_lists();
+		let frozen = self.frozen_vars();
+		let mut changed = true;
+		while changed {
+			changed = false;
+			//a literal is pure if its negation is in no clause left
+			for (var, &is_frozen) in frozen.iter().enumerate() {
+				if is_frozen || self.model.var[var] != VUndef || self.model.eliminated[var] {
+					continue;
+				}
+				let lit = Lit::create(var, true);
+				let pos = self.live_clauses(&occurs[lit.index()]);
+				let neg = self.live_clauses(&occurs[(!lit).index()]);
+				let (pure, clauses) = match (pos.is_empty(), neg.is_empty()) {
+					(false, true) => (lit, pos),
+					(true, false) => (!lit, neg),
+					_ => continue,
+				};
+				for ci in clauses {
+					self.remove_clause(ci, Some(pure));
+				}
+				self.stats.pure_literals += 1;
+				changed = true;
+			}
+			
+			for ci in self.cnf.refs() {
+				if self.cnf.is_learnt(ci) || self.cnf.is_deleted(ci) || self.is_satisfied(ci) {
+					continue;
+				}
+				let lits = self.cnf.lits(ci).to_vec();
+				for &lit in &lits {
+					let var = lit.var_num();
+					if frozen[var] || self.model.var[var] != VUndef || self.model.eliminated[var] {
+						continue;
+					}
+					let partners = self.live_clauses(&occurs[(!lit).index()]);
+					if partners.len() <= BLOCKED_PARTNER_LIMIT && partners.iter().all(|&d| self.is_blocked(&lits, lit, d)) {
+						self.remove_clause(ci, Some(lit));
+						self.stats.blocked_clauses += 1;
+						changed = true;
+						break;
+					}
+				}
+			}
+		}
+	}
+	
+	//if the resolvent of the literals with clause D on lit is a tautology
+	fn is_blocked(&self, lits: &[Lit], lit: Lit, d: CRef) -> bool {
+		let other = self.cnf.lits(d);
+		lits.iter().any(|&l| l != lit && other.contains(&!l))
+	}
+	
+	//bounded variable elimination: replace the clauses of a variable by their resolvents, if there
+	//are not more of them
+	fn eliminate_vars(&mut self) {
+		let mut occurs = self.occurrence_lists();
+		let frozen = self.frozen_vars();
+		let mut vars: Vec<usize> = (0..self.num_var).filter(|&v| !frozen[v] && self.model.var[v] == VUndef && !self.model.eliminated[v]).collect();
+		//the variables with the fewest resolutions first
+		vars.sort_by_key(|&v| occurs[v * 2].len() * occurs[v * 2 + 1].len());
+		
+		for var in vars {
+			if !self.status {
+				return;
+			}
+			if self.model.var[var] != VUndef {
+				continue;
+			}
+			let lit = Lit::create(var, true);
+			let pos = self.live_clauses(&occurs[lit.index()]);
+			let neg = self.live_clauses(&occurs[(!lit).index()]);
+			if pos.len() * neg.len() > ELIM_RESOLUTION_LIMIT {
+				continue;
+			}
+			let mut resolvents = Vec::<Vec<Lit>>::new();
+			let mut bounded = true;
+			'resolve: for &p in &pos {
+				for &n in &neg {
+					if let Some(resolvent) = self.resolve(p, n, var) {
+						if resolvents.len() == pos.len() + neg.len() || resolvent.len() > ELIM_RESOLVENT_LIMIT {
+							bounded = false;
+							break 'resolve;
+						}
+						resolvents.push(resolvent);
+					}
+				}
+			}
+			if !bounded {
+				continue;
+			}
+			
+			for resolvent in resolvents {
+				if let Some(ci) = self.add_derived(resolvent) {
+					for l in self.cnf.lits(ci) {
+						occurs[l.index()].push(ci);
+					}
+				}
+				if !self.status {
+					return;
+				}
+			}
+			//a unit resolvent may assign the variable, then its clauses are kept
+			if self.model.var[var] != VUndef {
+				continue;
+			}
+			for ci in pos {
+				self.remove_clause(ci, Some(lit));
+			}
+			for ci in neg {
+				self.remove_clause(ci, Some(!lit));
+			}
+			//a variable without any live clause is eliminated as well, so it must be restored by a later clause
+			self.model.eliminated[var] = true;
+			self.model.witness[var] = true;
+			self.stats.eliminated_vars += 1;
+		}
+		
+		//the learnt clauses must not assign an eliminated variable
+		for ci in self.cnf.refs() {
+			if self.cnf.is_learnt(ci) && !self.cnf.is_deleted(ci) && self.cnf.lits(ci).iter().any(|l| self.model.eliminated[l.var_num()]) {
+				self.remove_clause(ci, None);
+			}
+		}
+	}
+	
+	//remove the original clauses subsumed by another one, and strengthen the clauses by
+	//self-subsuming resolution: (a \/ b) strengthens (~a \/ b \/ c) to (b \/ c)
+	fn subsume_clauses(&mut self) {
+		let mut occurs = self.occurrence_lists();
+		let mut queue: Vec<CRef> = self.cnf.refs().into_iter().filter(|&ci| !self.cnf.is_learnt(ci) && !self.is_satisfied(ci)).collect();
+		//the shorter clauses subsume more
+		queue.sort_by_key(|&ci| self.cnf.lits(ci).len());
+		
+		let mut i = 0;
+		while i < queue.len() && self.status {
+			let c = queue[i];
+			i += 1;
+			if self.cnf.is_deleted(c) || self.is_satisfied(c) {
+				continue;
+			}
+			//every clause C subsumes or strengthens contains the variable of C which occurs the least
+			let lit = *self.cnf.lits(c).iter().min_by_key(|l| occurs[l.index()].len() + occurs[(!**l).index()].len()).unwrap();
+			let candidates: Vec<CRef> = occurs[lit.index()].iter().chain(occurs[(!lit).index()].iter()).cloned().collect();
+			for d in candidates {
+				if d == c || self.cnf.is_deleted(d) || self.cnf.lits(d).len() < self.cnf.lits(c).len() || self.is_satisfied(d) {
+					continue;
+				}
+				match self.subsumes(c, d) {
+					Some(None) => {
+						self.remove_clause(d, None);
+						self.stats.subsumed_clauses += 1;
+					},
+					Some(Some(removed)) => {
+						let lits = self.cnf.lits(d).iter().cloned().filter(|&l| l != removed).collect();
+						//the strengthened clause is added before D is deleted, so that the proof can derive it
+						let added = self.add_derived(lits);
+						self.remove_clause(d, None);
+						self.stats.strengthened_clauses += 1;
+						if let Some(ci) = added {
+							for l in self.cnf.lits(ci) {
+								occurs[l.index()].push(ci);
+							}
+							queue.push(ci);
+						}
+						if !self.status {
+							return;
+						}
+					},
+					None => {},
+				}
+			}
+		}
+	}
+	
+	//failed literal probing: assign each literal in turn and propagate it
+	//a literal which leads to a conflict is false, and a literal implied by both values of a variable
+	//is true, and each literal implied through a longer clause gets a binary clause with the probe
+	//(hyper-binary resolution)
+	fn probe_literals(&mut self) {
+		let start = self.stats.propagations;
+		//the probes would overwrite the saved phases
+		let phase = self.model.phase.clone();
+		let mut implied = vec![false; self.num_var * 2];
+		for var in 0..self.num_var {
+			if !self.status || self.stats.propagations - start > PROBE_PROPAGATION_LIMIT {
+				break;
+			}
+			if self.model.var[var] != VUndef || self.model.eliminated[var] {
+				continue;
+			}
+			let lit = Lit::create(var, true);
+			let pos = match self.probe(lit) {
+				Some(lits) => lits,
+				None => continue,
+			};
+			for l in &pos {
+				implied[l.index()] = true;
+			}
+			let neg = self.probe(!lit);
+			for l in &pos {
+				implied[l.index()] = false;
+			}
+			let common: Vec<Lit> = match neg {
+				Some(neg) => neg.into_iter().filter(|l| implied[l.index()]).collect(),
+				None => continue,
+			};
+			for l in common {
+				if self.model.lit_value(l) != VUndef {
+					continue;
+				}
+				//the unit follows from the binary clauses of both probes
+				if let Some(ref mut proof) = self.proof {
+					proof.add(&[!lit, l]);
+					proof.add(&[lit, l]);
+				}
+				self.add_derived(vec![l]);
+				if let Some(ref mut proof) = self.proof {
+					proof.delete(&[!lit, l]);
+					proof.delete(&[lit, l]);
+				}
+				self.stats.failed_literals += 1;
+				if !self.status {
+					break;
+				}
+			}
+		}
+		self.model.phase = phase;
+	}
+	
+	//assign the literal at a new decision level and propagate it, then undo it
+	//return the literals it implies, or None if it fails and its negation is learnt
+	fn probe(&mut self, lit: Lit) -> Option<Vec<Lit>> {
+		self.model.trail_lim.push(self.model.trail.len());
+		self.assign(lit, None);
+		if self.propagate().is_some() {
+			self.cancel_until(0);
+			self.add_derived(vec![!lit]);
+			self.stats.failed_literals += 1;
+			return None;
+		}
+		let start = self.model.trail_lim[0] + 1;
+		let implied = self.model.trail[start..].to_vec();
+		//the literals implied through a clause with more than one other literal of the probe level
+		let binaries: Vec<Lit> = implied.iter().cloned().filter(|l| {
+			let reason = self.model.reason[l.var_num()].unwrap();
+			self.cnf.lits(reason).iter().filter(|&&r| r != *l && self.model.level[r.var_num()] > 0).count() > 1
+		}).collect();
+		self.cancel_until(0);
+		for l in binaries {
+			if let Some(ref mut proof) = self.proof {
+				proof.add(&[!lit, l]);
+			}
+			self.attach_clause(&[!lit, l], true);
+			self.stats.hyper_binaries += 1;
+		}
+		Some(implied)
+	}
+	
+	//vivify the clauses longer than two if enough conflicts were found since it last ran, starting
+	//from the clause it stopped at, until the propagations run out
+	//it runs without any decision, between restarts
+	pub(super) fn vivify_clauses(&mut self) {
+		if self.config.vivify_interval == 0 || self.config.core_tracking || self.stats.conflicts < self.next_vivify {
+			return;
+		}
+		self.next_vivify = self.stats.conflicts + self.config.vivify_interval;
+		let start = self.stats.propagations;
+		//the assignments would overwrite the saved phases
+		let phase = self.model.phase.clone();
+		let refs = self.cnf.refs();
+		let first = refs.partition_point(|&cr| cr < self.vivify_pt);
+		for k in 0..refs.len() {
+			if !self.status || self.stats.propagations - start > VIVIFY_PROPAGATION_LIMIT {
+				break;
+			}
+			let ci = refs[(first + k) % refs.len()];
+			self.vivify_pt = ci + 1;
+			if self.cnf.is_deleted(ci) || self.cnf.lits(ci).len() <= 2 || self.is_satisfied(ci) {
+				continue;
+			}
+			self.vivify(ci);
+		}
+		self.model.phase = phase;
+		self.collect_garbage();
+	}
+	
+	//assign the negations of the literals of the clause one by one, and replace it by the literals
+	//assigned until a conflict is found or one of its literals is implied true
+	//the literals implied false are left out
+	fn vivify(&mut self, ci: CRef) {
+		let lits = self.cnf.lits(ci).to_vec();
+		let mut kept = Vec::<Lit>::new();
+		for &lit in &lits {
+			match self.model.lit_value(lit) {
+				VFalse => {},
+				VTrue => {
+					kept.push(lit);
+					break;
+				},
+				VUndef => {
+					kept.push(lit);
+					self.model.trail_lim.push(self.model.trail.len());
+					self.assign(!lit, None);
+					if self.propagate().is_some() {
+						break;
+					}
+				},
+			}
+		}
+		self.cancel_until(0);
+		if kept.len() == lits.len() {
+			return;
+		}
+		self.stats.vivified_clauses += 1;
+		//the shorter clause is added before the clause is deleted, so that the proof can derive it
+		if self.cnf.is_learnt(ci) && kept.len() >= 2 {
+			if let Some(ref mut proof) = self.proof {
+				proof.add(&kept);
+			}
+			let lbd = self.cnf.lbd(ci).min(kept.len());
+			let cr = self.attach_clause(&kept, true);
+			self.cnf.set_lbd(cr, lbd);
+			if kept.len() > 2 {
+				self.num_learnt += 1;
+			}
+		}else {
+			self.add_derived(kept);
+		}
+		self.remove_clause(ci, None);
+	}
+	
+	//Some(None) if every literal of clause C is in clause D, Some(Some(lit)) if every one but one is,
+	//and D has lit the negation of that one, None otherwise
+	fn subsumes(&self, c: CRef, d: CRef) -> Option<Option<Lit>> {
+		let lits = self.cnf.lits(d);
+		let mut removed = None;
+		for &lit in self.cnf.lits(c) {
+			if lits.contains(&lit) {
+				continue;
+			}
+			match removed {
+				None if lits.contains(&!lit) => removed = Some(!lit),
+				Some(r) if r == !lit => {},
+				_ => return None,
+			}
+		}
+		Some(removed)
+	}
+	
+	//resolvent of two clauses on the variable, without the literals false without any decision
+	//None if it is a tautology
+	fn resolve(&self, a: CRef, b: CRef, var: usize) -> Option<Vec<Lit>> {
+		let mut lits = Vec::<Lit>::new();
+		for &lit in self.cnf.lits(a).iter().chain(self.cnf.lits(b).iter()) {
+			if lit.var_num() == var || self.model.lit_value(lit) == VFalse || lits.contains(&lit) {
+				continue;
+			}
+			if lits.contains(&!lit) {
+				return None;
+			}
+			lits.push(lit);
+		}
+		Some(lits)
+	}
+	
+	//original clauses containing each literal, indexed by Lit::index
+	//the clauses satisfied without any decision and the tautologies are left out
+	fn occurrence_lists(&self) -> Vec<Vec<CRef>> {
+		let mut occurs = vec![Vec::<CRef>::new(); self.num_var * 2];
+		for ci in self.cnf.refs() {
+			if !self.cnf.is_learnt(ci) && !self.cnf.is_deleted(ci) && !self.is_satisfied(ci) {
+				for lit in self.cnf.lits(ci) {
+					occurs[lit.index()].push(ci);
+				}
+			}
+		}
+		occurs
+	}
+	
+	//the clauses of an occurrence list which are neither removed nor satisfied
+	//a clause with a repeated literal is listed once
+	fn live_clauses(&self, occurs: &[CRef]) -> Vec<CRef> {
+		let mut clauses: Vec<CRef> = occurs.iter().cloned().filter(|&ci| !self.cnf.is_deleted(ci) && !self.is_satisfied(ci)).collect();
+		clauses.dedup();
+		clauses
+	}
+	
+	//the variables of the assumptions, which must keep their clauses
+	fn frozen_vars(&self) -> Vec<bool> {
+		let mut frozen = vec![false; self.num_var];
+		for lit in &self.assumptions {
+			frozen[lit.var_num()] = true;
+		}
+		frozen
+	}
+	
+	//if the clause is satisfied without any decision, or is a tautology
+	fn is_satisfied(&self, ci: CRef) -> bool {
+		let lits = self.cnf.lits(ci);
+		lits.iter().any(|&l| self.model.lit_value(l) == VTrue || lits.contains(&!l))
+	}
+	
+	//add a clause implied by the CNF as an original clause, and propagate it if it is unit
+	//return its reference if it is added into the CNF
+	fn add_derived(&mut self, lits: Vec<Lit>) -> Option<CRef> {
+		if let Some(ref mut proof) = self.proof {
+			proof.add(&lits);
+		}
+		//insert_clause leaves out a unit which is already true, but it may be true only through the
+		//clauses this one replaces
+		if lits.len() == 1 && self.model.lit_value(lits[0]) == VTrue {
+			return Some(self.attach_clause(&lits, false));
+		}
+		let size = self.cnf.size();
+		let mut clause = Clause::new();
+		for lit in lits {
+			clause.push(lit);
+		}
+		self.insert_clause(clause).unwrap_or(false);
+		if self.status {
+			if let Some(confl) = self.propagate() {
+				self.set_unsat(confl);
+			}
+		}
+		if self.cnf.size() > size {Some(size as CRef)} else {None}
+	}
+	
+	//remove a clause from the CNF, and push it on the reconstruction stack with the literal which
+	//satisfies it if the model does not
+	pub(super) fn remove_clause(&mut self, ci: CRef, witness: Option<Lit>) {
+		let lits = self.cnf.lits(ci).to_vec();
+		if lits.len() >= 2 {
+			for lit in &lits[..2] {
+				self.model.watches[lit.index()].retain(|w| w.clause != ci);
+			}
+		}
+		if let Some(ref mut proof) = self.proof {
+			proof.delete(&lits);
+		}
+		if !self.cnf.is_learnt(ci) {
+			for lit in &lits {
+				self.model.occurs[lit.index()] -= 1;
+			}
+		}else if lits.len() > 2 {
+			self.num_learnt -= 1;
+		}
+		//a clause satisfied without any decision may be the reason of its first literal
+		if let Some(first) = lits.first() {
+			if self.model.reason[first.var_num()] == Some(ci) {
+				self.model.reason[first.var_num()] = None;
+			}
+		}
+		self.cnf.delete(ci);
+		if let Some(witness) = witness {
+			self.model.witness[witness.var_num()] = true;
+			self.elim_stack.push((witness, lits));
+		}
+	}
+}
+
+//strongly connected components of a graph, by Tarjan's algorithm without recursion
+//return the component of each node
+fn strongly_connected(graph: &[Vec<usize>]) -> Vec<usize> {
+	let n = graph.len();
+	let mut index = vec![usize::MAX; n];
+	let mut low = vec![0; n];
+	let mut on_stack = vec![false; n];
+	let mut stack = Vec::<usize>::new();
+	let mut component = vec![usize::MAX; n];
+	let mut next = 0;
+	let mut num = 0;
+	for root in 0..n {
+		if index[root] != usize::MAX {
+			continue;
+		}
+		//each node visited, with the number of its edges already followed
+		let mut path = vec![(root, 0)];
+		index[root] = next;
+		low[root] = next;
+		next += 1;
+		stack.push(root);
+		on_stack[root] = true;
+		while let Some(&(v, i)) = path.last() {
+			if i < graph[v].len() {
+				path.last_mut().unwrap().1 += 1;
+				let w = graph[v][i];
+				if index[w] == usize::MAX {
+					index[w] = next;
+					low[w] = next;
+					next += 1;
+					stack.push(w);
+					on_stack[w] = true;
+					path.push((w, 0));
+				}else if on_stack[w] {
+					low[v] = low[v].min(index[w]);
+				}
+				continue;
+			}
+			path.pop();
+			if let Some(&(u, _)) = path.last() {
+				low[u] = low[u].min(low[v]);
+			}
+			if low[v] == index[v] {
+				loop {
+					let w = stack.pop().unwrap();
+					on_stack[w] = false;
+					component[w] = num;
+					if w == v {
+						break;
+					}
+				}
+				num += 1;
+			}
+		}
+	}
+	component
+}
+
+#[cfg(test)]
+mod tests {
+	use sat_lib::{Solver, SolverConfig, SolveResult, Lit};
+	use sat_lib::VarValue::*;
+	
+	//a solver with the clauses in DIMACS format
+	fn solver(config: SolverConfig, clauses: &[&[i64]]) -> Solver {
+		let mut solver = Solver::with_config(config);
+		for clause in clauses {
+			solver.add_clause_from_lits(clause.iter().map(|&n| Lit::from_dimacs(n).unwrap()).collect()).unwrap();
+		}
+		solver
+	}
+	
+	//the clauses left in the CNF in DIMACS format, with their literals sorted
+	fn clauses(solver: &Solver, learnt: bool) -> Vec<Vec<i64>> {
+		let mut clauses: Vec<Vec<i64>> = solver.cnf.refs().into_iter().filter(|&ci| solver.cnf.is_learnt(ci) == learnt && !solver.cnf.is_deleted(ci)).map(|ci| {
+			let mut lits: Vec<i64> = solver.cnf.lits(ci).iter().map(|l| l.to_dimacs()).collect();
+			lits.sort();
+			lits
+		}).collect();
+		clauses.sort();
+		clauses
+	}
+	
+	#[test]
+	fn subsume_and_strengthen() {
+		//(1 2) subsumes (1 2 3) and strengthens (-1 2 4) to (2 4)
+		let config = SolverConfig { subsumption: true, ..Default::default() };
+		let mut solver = solver(config, &[&[1, 2], &[1, 2, 3], &[-1, 2, 4]]);
+		assert!(solver.simplify());
+		assert_eq!(clauses(&solver, false), vec![vec![1, 2], vec![2, 4]]);
+		assert_eq!(solver.stats.subsumed_clauses, 1);
+		assert_eq!(solver.stats.strengthened_clauses, 1);
+	}
+	
+	#[test]
+	fn probe_failed_literal() {
+		//1 implies both 2 and 3, which conflict, so ~1 is learnt
+		let config = SolverConfig { probing: true, ..Default::default() };
+		let mut solver = solver(config, &[&[-1, 2], &[-1, 3], &[-2, -3]]);
+		assert!(solver.simplify());
+		assert_eq!(solver.model.var[0], VFalse);
+		assert_eq!(solver.model.level[0], 0);
+		assert!(solver.model.trail_lim.is_empty());
+		assert_eq!(solver.stats.failed_literals, 1);
+	}
+	
+	#[test]
+	fn hyper_binary_resolution() {
+		//1 implies 4 through (-2 -3 4), which has two literals of the probe, so (-1 4) is learnt
+		let config = SolverConfig { probing: true, ..Default::default() };
+		let mut solver = solver(config, &[&[-1, 2], &[-1, 3], &[-2, -3, 4]]);
+		assert!(solver.simplify());
+		assert_eq!(clauses(&solver, true), vec![vec![-1, 4]]);
+		assert_eq!(solver.stats.hyper_binaries, 1);
+		assert_eq!(solver.stats.failed_literals, 0);
+	}
+	
+	#[test]
+	fn substitute_equivalent_literals() {
+		//1, 2 and 3 are equivalent, so 2 and 3 are replaced by 1
+		let config = SolverConfig { equivalence: true, ..Default::default() };
+		let mut solver = solver(config, &[&[-1, 2], &[-2, 3], &[-3, 1], &[2, 3, 4]]);
+		assert!(solver.simplify());
+		assert_eq!(clauses(&solver, false), vec![vec![1, 4]]);
+		assert!(solver.model.eliminated[1] && solver.model.eliminated[2]);
+		assert_eq!(solver.stats.substituted_vars, 2);
+		
+		assert_eq!(solver.solve(), SolveResult::Sat);
+		let model = solver.get_model();
+		assert!(model[1] == model[0] && model[2] == model[0]);
+	}
+	
+	#[test]
+	fn literal_equivalent_to_its_negation() {
+		//1 implies 2, which implies ~1, and ~1 implies ~2, which implies 1
+		let config = SolverConfig { equivalence: true, ..Default::default() };
+		let mut solver = solver(config, &[&[-1, 2], &[-2, -1], &[1, -2], &[2, 1]]);
+		assert!(!solver.simplify());
+		assert_eq!(solver.stats.conflicts, 0);
+		assert_eq!(solver.solve(), SolveResult::Unsat);
+	}
+	
+	#[test]
+	fn remove_pure_literal_and_blocked_clauses() {
+		//3 is pure, then (1 2) is blocked on 1 by (-2 -1), which is left without a partner on -2
+		let cnf: &[&[i64]] = &[&[3, 1], &[1, 2], &[-2, -1]];
+		let config = SolverConfig { blocked_clauses: true, ..Default::default() };
+		let mut solver = solver(config, cnf);
+		assert!(solver.simplify());
+		assert!(clauses(&solver, false).is_empty());
+		assert_eq!(solver.stats.pure_literals, 1);
+		assert_eq!(solver.stats.blocked_clauses, 2);
+		
+		//the search sets every variable false, which the model must repair by making 1 true
+		assert_eq!(solver.solve(), SolveResult::Sat);
+		let model = solver.get_model();
+		assert_eq!(model[0], VTrue);
+		assert!(cnf.iter().all(|c| c.iter().any(|&n| {
+			let lit = Lit::from_dimacs(n).unwrap();
+			model[lit.var_num()] == lit.get_value()
+		})));
+	}
+	
+	#[test]
+	fn vivify_original_clause() {
+		//~1 implies ~3 through (1 -3), then ~2 falsifies (1 2 3), which shrinks to (1 2)
+		let config = SolverConfig { vivify_interval: 1, ..Default::default() };
+		let mut solver = solver(config, &[&[1, 2, 3], &[1, -3]]);
+		solver.vivify_clauses();
+		assert_eq!(clauses(&solver, false), vec![vec![-3, 1], vec![1, 2]]);
+		assert_eq!(solver.stats.vivified_clauses, 1);
+	}
+}
@@ -0,0 +1,254 @@
+use sat_lib::{Clause, Lit, VarValue, SolveResult, SlsConfig, SlsAlgorithm};
+use rand::{Rng, SeedableRng, StdRng};
+
+#[derive (Debug)]
+///Stochastic local search (WalkSAT or ProbSAT). Starting from a random assignment, it flips the
+///variables of falsified clauses until every clause is satisfied. It can only prove a CNF
+///satisfiable, so it gives up with SolveResult::Unknown after its budget of flips.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///let mut solver = Solver::new();
+///let x = Lit::create_lits(&solver.create_vars(3));
+///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+///solver.add_clause_from_lits(vec![!x[0], x[2]]).unwrap();
+///solver.add_clause_from_lits(vec![!x[1], !x[2]]).unwrap();
+///
+///let mut sls = LocalSearch::new(&solver.get_oringin_clauses(), 3, SlsConfig::default());
+///println!("{}", sls.solve()); //SAT
+///println!("{}", sls.get_model().len()); //3
+///```
+pub struct LocalSearch {
+	clauses: 	Vec<Vec<Lit>>,		//clauses without tautologies or duplicate literals
+	occurs: 	Vec<Vec<usize>>,	//clauses containing each literal
+	num_var: 	usize,
+	config: 	SlsConfig,
+	value: 		Vec<bool>,			//current assignment
+	num_true: 	Vec<usize>,			//number of true literals of each clause
+	breaks: 	Vec<usize>,			//number of clauses each variable is the only true literal of
+	falsified: 	Vec<usize>,			//clauses without true literal
+	position: 	Vec<usize>,			//position of each falsified clause in falsified
+	model: 		Vec<VarValue>,		//model found by the last solve
+	flips: 		u64,				//number of flips of the last solve
+	rng: 		StdRng,				//random number generator seeded by config.seed
+	empty: 		bool,				//if the CNF has an empty clause
+}
+
+impl LocalSearch {
+	///Create a local search over the clauses of num_var variables
+	pub fn new(clauses: &[Clause], num_var: usize, config: SlsConfig) -> Self {
+		let mut num_var = num_var;
+		let mut lits = Vec::<Vec<Lit>>::new();
+		let mut empty = false;
+		for clause in clauses {
+			if let Some(max) = clause.get_max() {
+				num_var = num_var.max(max + 1);
+			}
+			let mut c = Vec::<Lit>::new();
+			let mut tautology = false;
+			for &lit in &clause.vec_lit {
+				if c.contains(&!lit) {
+					tautology = true;
+				}else if !c.contains(&lit) {
+					c.push(lit);
+				}
+			}
+			if c.is_empty() {
+				empty = true;
+			}else if !tautology {
+				lits.push(c);
+			}
+		}
+		let mut occurs = vec![Vec::<usize>::new(); num_var * 2];
+		for (i, clause) in lits.iter().enumerate() {
+			for lit in clause {
+				occurs[lit.index()].push(i);
+			}
+		}
+		LocalSearch {
+			num_true: 	vec![0; lits.len()],
+			position: 	vec![usize::MAX; lits.len()],
+			clauses: 	lits,
+			occurs,
+			num_var,
+			config,
+			value: 		vec![false; num_var],
+			breaks: 	vec![0; num_var],
+			falsified: 	Vec::<usize>::new(),
+			model: 		Vec::<VarValue>::new(),
+			flips: 		0,
+			rng: 		StdRng::from_seed(&[config.seed as usize][..]),
+			empty,
+		}
+	}
+	
+	///Search for a model. Return SAT if a model is found, UNSAT if the CNF has an empty clause,
+	///or UNKNOWN if every try runs out of flips.
+	pub fn solve(&mut self) -> SolveResult {
+		self.model.clear();
+		self.flips = 0;
+		if self.empty {
+			return SolveResult::Unsat;
+		}
+		for _ in 0..self.config.max_tries {
+			self.restart();
+			for _ in 0..self.config.max_flips {
+				if self.falsified.is_empty() {
+					break;
+				}
+				let i = self.random(self.falsified.len());
+				let clause = self.falsified[i];
+				let var = match self.config.algorithm {
+					SlsAlgorithm::WalkSat { noise } => self.pick_walksat(clause, noise),
+					SlsAlgorithm::ProbSat { cb } => self.pick_probsat(clause, cb),
+				};
+				self.flip(var);
+				self.flips += 1;
+			}
+			if self.falsified.is_empty() && self.verify() {
+				self.model = self.value.iter().map(|&v| if v {VarValue::VTrue} else {VarValue::VFalse}).collect();
+				return SolveResult::Sat;
+			}
+		}
+		SolveResult::Unknown
+	}
+	
+	///Return the model found by the last solve, which is verified against every clause
+	pub fn get_model(&self) -> &[VarValue] {
+		&self.model
+	}
+	
+	///Return the number of flips of the last solve
+	pub fn get_flips(&self) -> u64 {
+		self.flips
+	}
+	
+	//start from a random assignment
+	fn restart(&mut self) {
+		for v in 0..self.num_var {
+			self.value[v] = self.rng.gen();
+			self.breaks[v] = 0;
+		}
+		self.falsified.clear();
+		for c in 0..self.clauses.len() {
+			let mut num_true = 0;
+			let mut last = 0;
+			for &lit in &self.clauses[c] {
+				if self.is_true(lit) {
+					num_true += 1;
+					last = lit.var_num();
+				}
+			}
+			self.num_true[c] = num_true;
+			self.position[c] = usize::MAX;
+			match num_true {
+				0 => self.add_falsified(c),
+				1 => self.breaks[last] += 1,
+				_ => {},
+			}
+		}
+	}
+	
+	//WalkSAT: a variable breaking nothing, else a random one with probability noise, else the one
+	//breaking the fewest clauses
+	fn pick_walksat(&mut self, clause: usize, noise: f64) -> usize {
+		let len = self.clauses[clause].len();
+		let min = self.clauses[clause].iter().map(|l| self.breaks[l.var_num()]).min().unwrap();
+		if min > 0 && self.random_f64() < noise {
+			let i = self.random(len);
+			return self.clauses[clause][i].var_num();
+		}
+		let best: Vec<usize> = self.clauses[clause].iter().map(|l| l.var_num()).filter(|&v| self.breaks[v] == min).collect();
+		best[self.random(best.len())]
+	}
+	
+	//ProbSAT: a variable with probability proportional to cb ^ -break
+	fn pick_probsat(&mut self, clause: usize, cb: f64) -> usize {
+		let weights: Vec<f64> = self.clauses[clause].iter().map(|l| cb.powi(-(self.breaks[l.var_num()] as i32))).collect();
+		let mut r = self.random_f64() * weights.iter().sum::<f64>();
+		for (i, w) in weights.iter().enumerate() {
+			if r < *w {
+				return self.clauses[clause][i].var_num();
+			}
+			r -= w;
+		}
+		self.clauses[clause].last().unwrap().var_num()
+	}
+	
+	//flip the variable, and update the true literal counts, the break counts and the falsified clauses
+	fn flip(&mut self, var: usize) {
+		self.value[var] = !self.value[var];
+		let true_index = var * 2 + if self.value[var] {0} else {1};
+		let false_index = true_index ^ 1;
+		for i in 0..self.occurs[true_index].len() {
+			let c = self.occurs[true_index][i];
+			self.num_true[c] += 1;
+			match self.num_true[c] {
+				1 => {
+					self.remove_falsified(c);
+					self.breaks[var] += 1;
+				},
+				//the other true literal is not the only one any more
+				2 => {
+					let other = self.only_true(c, var);
+					self.breaks[other] -= 1;
+				},
+				_ => {},
+			}
+		}
+		for i in 0..self.occurs[false_index].len() {
+			let c = self.occurs[false_index][i];
+			self.num_true[c] -= 1;
+			match self.num_true[c] {
+				0 => {
+					self.add_falsified(c);
+					self.breaks[var] -= 1;
+				},
+				1 => {
+					let other = self.only_true(c, var);
+					self.breaks[other] += 1;
+				},
+				_ => {},
+			}
+		}
+	}
+	
+	//the variable of the true literal of the clause, other than the given variable
+	fn only_true(&self, clause: usize, var: usize) -> usize {
+		self.clauses[clause].iter().find(|&&l| l.var_num() != var && self.is_true(l)).unwrap().var_num()
+	}
+	
+	fn add_falsified(&mut self, clause: usize) {
+		self.position[clause] = self.falsified.len();
+		self.falsified.push(clause);
+	}
+	
+	fn remove_falsified(&mut self, clause: usize) {
+		let pos = self.position[clause];
+		let last = *self.falsified.last().unwrap();
+		self.falsified.swap_remove(pos);
+		if last != clause {
+			self.position[last] = pos;
+		}
+		self.position[clause] = usize::MAX;
+	}
+	
+	//check the assignment against every clause
+	fn verify(&self) -> bool {
+		self.clauses.iter().all(|c| c.iter().any(|&l| self.is_true(l)))
+	}
+	
+	fn is_true(&self, lit: Lit) -> bool {
+		self.value[lit.var_num()] == (lit.get_value() == VarValue::VTrue)
+	}
+	
+	fn random(&mut self, n: usize) -> usize {
+		self.rng.gen_range(0, n)
+	}
+	
+	fn random_f64(&mut self) -> f64 {
+		self.rng.next_f64()
+	}
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+use std::time::Duration;
+
+#[derive (Debug, Clone, Default, PartialEq)]
+///Statistics of a solver, accumulated over every call of solve. They are updated while the
+///search runs, and can be read with Solver::get_stats.
+///
+///# Examples
+///
+///```
+///# use sat::sat_lib::*;
+///let mut solver = Solver::new();
+///let x = Lit::create_lits(&solver.create_vars(2));
+///solver.add_clause_from_lits(vec![x[0], x[1]]).unwrap();
+///solver.solve();
+///
+///let stats = solver.get_stats();
+///println!("{}", stats.decisions); //2
+///println!("{}", stats);
+///```
+pub struct SolverStats {
+	///Number of calls of solve.
+	pub solves: 			u64,
+	///Number of decisions.
+	pub decisions: 			u64,
+	///Number of propagated literals.
+	pub propagations: 		u64,
+	///Number of conflicts.
+	pub conflicts: 			u64,
+	///Number of restarts.
+	pub restarts: 			u64,
+	///Number of learnt clauses.
+	pub learnt_clauses: 	u64,
+	///Total number of literals in the learnt clauses.
+	pub learnt_literals: 	u64,
+	///Number of learnt clauses removed from the CNF.
+	pub deleted_clauses: 	u64,
+	///Number of reductions of the learnt clauses.
+	pub reductions: 		u64,
+	///Number of variables eliminated by preprocessing.
+	pub eliminated_vars: 	u64,
+	///Number of clauses removed because another clause subsumes them.
+	pub subsumed_clauses: 	u64,
+	///Number of literals removed from clauses by self-subsuming resolution.
+	pub strengthened_clauses: 	u64,
+	///Number of literals learnt by probing.
+	pub failed_literals: 	u64,
+	///Number of binary clauses learnt by hyper-binary resolution.
+	pub hyper_binaries: 	u64,
+	///Number of variables replaced by an equivalent literal.
+	pub substituted_vars: 	u64,
+	///Number of pure literals whose clauses are removed.
+	pub pure_literals: 		u64,
+	///Number of blocked clauses removed.
+	pub blocked_clauses: 	u64,
+	///Number of clauses shortened by vivification.
+	pub vivified_clauses: 	u64,
+	///Highest decision level reached.
+	pub max_decision_level: usize,
+	///Total time spent in solve.
+	pub solve_time: 		Duration,
+	///Time spent simplifying the CNF before the search.
+	pub simplify_time: 		Duration,
+	///Time spent in the search after simplifying, updated at every restart.
+	pub search_time: 		Duration,
+	///Time spent reducing the learnt clauses.
+	pub reduce_time: 		Duration,
+}
+
+impl fmt::Display for SolverStats {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "solves: {}", self.solves)?;
+		writeln!(f, "decisions: {}", self.decisions)?;
+		writeln!(f, "propagations: {}", self.propagations)?;
+		writeln!(f, "conflicts: {}", self.conflicts)?;
+		writeln!(f, "restarts: {}", self.restarts)?;
+		writeln!(f, "learnt_clauses: {}", self.learnt_clauses)?;
+		writeln!(f, "learnt_literals: {}", self.learnt_literals)?;
+		writeln!(f, "deleted_clauses: {}", self.deleted_clauses)?;
+		writeln!(f, "reductions: {}", self.reductions)?;
+		writeln!(f, "eliminated_vars: {}", self.eliminated_vars)?;
+		writeln!(f, "subsumed_clauses: {}", self.subsumed_clauses)?;
+		writeln!(f, "strengthened_clauses: {}", self.strengthened_clauses)?;
+		writeln!(f, "failed_literals: {}", self.failed_literals)?;
+		writeln!(f, "hyper_binaries: {}", self.hyper_binaries)?;
+		writeln!(f, "substituted_vars: {}", self.substituted_vars)?;
+		writeln!(f, "pure_literals: {}", self.pure_literals)?;
+		writeln!(f, "blocked_clauses: {}", self.blocked_clauses)?;
+		writeln!(f, "vivified_clauses: {}", self.vivified_clauses)?;
+		writeln!(f, "max_decision_level: {}", self.max_decision_level)?;
+		writeln!(f, "solve_time: {:.6}", self.solve_time.as_secs_f64())?;
+		writeln!(f, "simplify_time: {:.6}", self.simplify_time.as_secs_f64())?;
+		writeln!(f, "search_time: {:.6}", self.search_time.as_secs_f64())?;
+		write!(f, "reduce_time: {:.6}", self.reduce_time.as_secs_f64())
+	}
+}
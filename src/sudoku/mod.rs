@@ -13,33 +13,27 @@ pub fn sudoku(in_file: Option<String>) {
 	let mut s = Mapper::new();
 	s.build_clauses();
 	File::create("foo.txt").unwrap();
-	let mut file = OpenOptions::new().read(true).write(true).append(true).open("./foo.txt").unwrap();
-	file.write(s.out.as_bytes()).unwrap();
+	let mut file = OpenOptions::new().read(true).append(true).open("./foo.txt").unwrap();
+	file.write_all(s.out.as_bytes()).unwrap();
 	
-	let file_name;
-	match in_file {
-		Some(name) => file_name = name,
-		None => file_name = "./SudokuPuzzle.txt".to_string(),
-	}
+	let file_name = match in_file {
+		Some(name) => name,
+		None => "./SudokuPuzzle.txt".to_string(),
+	};
 	let file_puzzle = File::open(file_name).unwrap();
 	let reader = BufReader::new(file_puzzle);
 
 	let mut input = Vec::new();
 
-	for line in reader.lines() {
-    	match line {
-    		Ok(c) => {
-    			if c.starts_with("c")  || c.len() == 0 { continue }
-    			let mut iter = c.split_whitespace();
-    			let mut temp = format!("{}{}{} ", iter.next().unwrap(),iter.next().unwrap(),iter.next().unwrap());
-    			// println!("{:?}",temp );
-    			file.write(temp.as_bytes()).unwrap();
-    			temp.pop();
-    			input.push(temp.parse::<usize>().unwrap());
-    			file.write("0\n".as_bytes()).unwrap(); 
-    		},
-    		Err(_) => {},
-    	}
+	for c in reader.lines().map_while(Result::ok) {
+		if c.starts_with('c')  || c.is_empty() { continue }
+		let mut iter = c.split_whitespace();
+		let mut temp = format!("{}{}{} ", iter.next().unwrap(),iter.next().unwrap(),iter.next().unwrap());
+		// println!("{:?}",temp );
+		file.write_all(temp.as_bytes()).unwrap();
+		temp.pop();
+		input.push(temp.parse::<usize>().unwrap());
+		file.write_all("0\n".as_bytes()).unwrap(); 
 	}
 
 	let mut v_ori = Vec::new();
@@ -62,29 +56,22 @@ pub fn sudoku(in_file: Option<String>) {
 	let file_new = File::open("./foo.txt").unwrap();
 	let reader = BufReader::new(file_new);
 
-	for line in reader.lines() {
-		match line{
-			Ok(c) => {
-				let mut iter = c.split_whitespace();
-				let mut lits = Vec::<Lit>::new();
-				while let Some(v) = iter.next(){
-					if let Ok(num) = v.parse::<i32>() {
-						if num < 0{
-							lits.push(!Lit::new(Var::new((-num) as usize)));
-						}else if num >0 {
-							lits.push(Lit::new(Var::new(num as usize)));
-						}
-					}
-				}
-				if lits.len() != 0 {
-					solver.add_clause_from_lits(lits).unwrap();
+	for c in reader.lines().map_while(Result::ok) {
+		let mut lits = Vec::<Lit>::new();
+		for v in c.split_whitespace() {
+			if let Ok(num) = v.parse::<i32>() {
+				if num < 0{
+					lits.push(!Lit::new(Var::new((-num) as usize)));
+				}else if num >0 {
+					lits.push(Lit::new(Var::new(num as usize)));
 				}
-
-			},
-			Err(_) => {},
+			}
+		}
+		if !lits.is_empty() {
+			solver.add_clause_from_lits(lits).unwrap();
 		}
 	}
-	let sat = solver.solve();
+	let sat = solver.solve() == SolveResult::Sat;
 	if !sat {
 		println!("No Solution");
 		return;
@@ -92,8 +79,8 @@ pub fn sudoku(in_file: Option<String>) {
 	let res = solver.get_model();
 
 	let mut v = Vec::new();
-	for i in 0..1000{
-		if res[i] == VarValue::VTrue{
+	for (i, val) in res.iter().enumerate().take(1000) {
+		if *val == VarValue::VTrue{
 			v.push(i)
 		}
 	}
@@ -109,15 +96,15 @@ pub fn sudoku(in_file: Option<String>) {
 	print_sudoku(&v2);
 }
 
-fn print_sudoku<T>(v: &Vec<Vec<T>>) 
+fn print_sudoku<T>(v: &[Vec<T>]) 
 where T: fmt::Display {
 	// println!("{:?}",input );
-	for i in 0..9{
+	for row in v.iter().take(9) {
 		println!("-------------------------------------");
-		for j in 0..9{
-			print!("| {} ", v[i][j]);
+		for cell in row.iter().take(9) {
+			print!("| {} ", cell);
 		}
-		print!("|\n");
+		println!("|");
 	}
 	println!("-------------------------------------");
 }
@@ -1,6 +1,11 @@
 use sat::sat_lib::*;
 use std::vec::Vec;
 use std::collections::HashSet;
+use std::time::Duration;
+use std::thread;
+use std::env;
+use std::fs::{self, File};
+use std::io::BufWriter;
 use rand::Rng;
 use time::now;
 
@@ -14,15 +19,87 @@ pub fn test() {
 	println!("**************************************************************\n");
 	sat_test();
 
+	println!("\n**************************************************************");
+	println!("****************    Clause Insertion Test     ****************");
+	println!("**************************************************************\n");
+	random_insertion_test(5000);
+
 	println!("\n**************************************************************");
 	println!("****************       Correctness Test       ****************");
 	println!("**************************************************************\n");
 	random_correctness_test(5000);
 
+	println!("\n**************************************************************");
+	println!("****************       Assumption  Test       ****************");
+	println!("**************************************************************\n");
+	random_assumption_test(2000);
+
+	println!("\n**************************************************************");
+	println!("****************       Incremental Test       ****************");
+	println!("**************************************************************\n");
+	random_incremental_test(2000);
+
+	println!("\n**************************************************************");
+	println!("****************       UNSAT Core  Test       ****************");
+	println!("**************************************************************\n");
+	random_core_test(1000);
+
+	println!("\n**************************************************************");
+	println!("****************       Enumeration Test       ****************");
+	println!("**************************************************************\n");
+	random_enumeration_test(500);
+
+	println!("\n**************************************************************");
+	println!("****************      Model Counting Test     ****************");
+	println!("**************************************************************\n");
+	random_count_test(2000);
+
+	println!("\n**************************************************************");
+	println!("****************         MaxSAT  Test         ****************");
+	println!("**************************************************************\n");
+	random_maxsat_test(1000);
+
+	println!("\n**************************************************************");
+	println!("****************      Local Search  Test      ****************");
+	println!("**************************************************************\n");
+	random_sls_test(1000);
+
+	println!("\n**************************************************************");
+	println!("****************      Preprocessing Test      ****************");
+	println!("**************************************************************\n");
+	random_simplify_test(2000, SolverConfig { elimination: true, ..Default::default() });
+	random_simplify_test(2000, SolverConfig { subsumption: true, ..Default::default() });
+	random_simplify_test(2000, SolverConfig { probing: true, ..Default::default() });
+	random_simplify_test(2000, SolverConfig { equivalence: true, ..Default::default() });
+	random_simplify_test(2000, SolverConfig { blocked_clauses: true, ..Default::default() });
+	random_simplify_test(2000, SolverConfig { vivify_interval: 1, restart: Restart::Luby { unit: 1 }, ..Default::default() });
+	random_simplify_test(2000, SolverConfig { elimination: true, subsumption: true, probing: true, equivalence: true, blocked_clauses: true, ..Default::default() });
+
+	println!("\n**************************************************************");
+	println!("****************         Budget  Test         ****************");
+	println!("**************************************************************\n");
+	budget_test();
+
+	println!("\n**************************************************************");
+	println!("****************        Interrupt Test        ****************");
+	println!("**************************************************************\n");
+	interrupt_test();
+
+	println!("\n**************************************************************");
+	println!("****************        Observer  Test        ****************");
+	println!("**************************************************************\n");
+	observer_test();
+
+	println!("\n**************************************************************");
+	println!("****************          Proof Test          ****************");
+	println!("**************************************************************\n");
+	proof_test(50);
+
 	println!("\n**************************************************************");
 	println!("****************       Efficiency  Test       ****************");
 	println!("**************************************************************\n");
-	random_efficiency_test(5);
+	random_efficiency_test(5, Branching::Vsids);
+	random_efficiency_test(5, Branching::Ordered);
 }
 
 fn sat_test() {
@@ -53,12 +130,60 @@ fn sat_test() {
 	
 	println!("{}", solver);	
 	let clauses = solver.get_oringin_clauses();
-	let sat = solver.solve();
+	let sat = solver.solve() == SolveResult::Sat;
 	println!("Match: {}", if sat {verify(&clauses, solver.get_model())} else {verify_unsat(&clauses, 10)});
 	print!("Model: ");
 	solver.print_model();
 }
 
+//clauses with duplicate and opposite literals, added after some units
+fn random_insertion_test(num: usize) {
+	let mut rng = rand::thread_rng();
+	let mut simplified = 0;
+	let mut satisfied = 0;
+	for _ in 0..num {
+		let mut solver = Solver::new();
+		let var_n = 10;
+		let x = Lit::create_lits(&solver.create_vars(var_n));
+		let mut clauses = Vec::<Clause>::new();
+		for _ in 0..rng.gen_range(1, 40) {
+			let mut clause = Clause::new();
+			for _ in 0..rng.gen_range(1, 6) {
+				let lit = x[rng.gen_range(0, var_n)];
+				clause.push(if rng.gen() {lit} else {!lit});
+			}
+			clauses.push(clause.clone());
+			match solver.add_clause_with_status(clause) {
+				Ok(ClauseStatus::Kept) => {},
+				Ok(ClauseStatus::Simplified) => simplified += 1,
+				Ok(ClauseStatus::Satisfied) => satisfied += 1,
+				Err(_) => break,
+			}
+		}
+		
+		//the literals of every clause kept are sorted by variable, without repeating one
+		for clause in solver.get_oringin_clauses() {
+			let lits = clause.get_all_lits();
+			if lits.windows(2).any(|w| w[0].var_num() >= w[1].var_num()) {
+				println!("Clause is not normalized: {}", clause);
+				return;
+			}
+		}
+		let sat = solver.solve() == SolveResult::Sat;
+		if sat && !verify(&clauses, solver.get_model()) {
+			println!("Wrong Model");
+			return;
+		}
+		if !sat && !verify_unsat(&clauses, var_n) {
+			println!("CNF is sat");
+			return;
+		}
+	}
+	println!("Total num of test: {}", num);
+	println!("Num of simplified clauses: {}\tNum of satisfied clauses: {}", simplified, satisfied);
+	println!("Test Passed");
+}
+
 fn random_correctness_test(num: usize) {
 	let mut sat_case = 0;
 	let mut unsat_case = 0;
@@ -66,7 +191,19 @@ fn random_correctness_test(num: usize) {
 		if i % 1000 == 0 {
 			println!("Correctness test num: {}", i);
 		} 
-		let mut solver = Solver::new();
+		//restart very often, so that restarts are exercised on small instances
+		let restart = match i / 2 % 4 {
+			0 => Restart::Never,
+			1 => Restart::Luby { unit: 1 },
+			2 => Restart::Geometric { first: 1, factor: 1.5 },
+			_ => Restart::Glucose { window: 2, k: 0.8 },
+		};
+		let config = SolverConfig {
+			branching: if i % 2 == 0 {Branching::Vsids} else {Branching::Ordered},
+			restart,
+			..Default::default()
+		};
+		let mut solver = Solver::with_config(config);
 		let var_n = 10;		// number of variables
 		{
 			let clause_ms = 5.;	// max size of each clause
@@ -84,7 +221,7 @@ fn random_correctness_test(num: usize) {
 		
 		let clauses = solver.get_oringin_clauses();
 		
-		let sat = solver.solve();
+		let sat = solver.solve() == SolveResult::Sat;
 		
 		if sat {
 			sat_case += 1;
@@ -105,21 +242,683 @@ fn random_correctness_test(num: usize) {
 	println!("Test Passed");
 }
 
-fn random_efficiency_test(num: usize) {
+fn random_assumption_test(num: usize) {
+	let mut rng = rand::thread_rng();
+	let mut sat_case = 0;
+	let mut unsat_case = 0;
+	for i in 1..num + 1 {
+		if i % 1000 == 0 {
+			println!("Assumption test num: {}", i);
+		}
+		let mut solver = Solver::new();
+		let var_n = 10;		// number of variables
+		let lits = Lit::create_lits(&solver.create_vars(var_n));
+		random_clauses(&mut solver, lits.clone(), var_n as f32, 5., 30., 0., false);
+		
+		let clauses = solver.get_oringin_clauses();
+		
+		//ask several times under different assumptions
+		for _ in 0..5 {
+			let mut assumptions = Vec::<Lit>::new();
+			for _ in 0..(rng.next_f32() * 5.).floor() as usize {
+				let lit = lits[(rng.next_f32() * var_n as f32).floor() as usize];
+				assumptions.push(if rng.gen() {lit} else {!lit});
+			}
+			
+			if solver.solve_with_assumptions(&assumptions) == SolveResult::Sat {
+				sat_case += 1;
+				let model = solver.get_model();
+				if !verify(&clauses, model) || assumptions.iter().any(|lit| lit.get_value() != model[lit.var_num()]) {
+					println!("Wrong Model");
+					return;
+				}
+			}else {
+				unsat_case += 1;
+				let failed = solver.get_failed_assumptions();
+				let mut with_failed = clauses.clone();
+				for lit in failed {
+					let mut clause = Clause::new();
+					clause.push(*lit);
+					with_failed.push(clause);
+				}
+				if failed.iter().any(|lit| !assumptions.contains(lit)) || !verify_unsat(&with_failed, var_n) {
+					println!("Wrong failed assumptions");
+					return;
+				}
+			}
+			if solver.get_oringin_clauses().len() != clauses.len() {
+				println!("CNF is changed");
+				return;
+			}
+		}
+	}
+	println!("\nTotal num of test: {}", num);
+	println!("Num of sat: {}\tNum of unsat: {}", sat_case, unsat_case);
+	println!("Test Passed");
+}
+
+fn random_incremental_test(num: usize) {
+	let mut sat_case = 0;
+	let mut unsat_case = 0;
+	for i in 1..num + 1 {
+		if i % 1000 == 0 {
+			println!("Incremental test num: {}", i);
+		}
+		let mut solver = Solver::new();
+		let var_n = 10;		// number of variables
+		let lits = Lit::create_lits(&solver.create_vars(var_n));
+		
+		//add clauses in batches and solve after each batch
+		loop {
+			random_clauses(&mut solver, lits.clone(), var_n as f32, 5., 12., 1., false);
+			let clauses = solver.get_oringin_clauses();
+			
+			if solver.solve() == SolveResult::Sat {
+				sat_case += 1;
+				if !verify(&clauses, solver.get_model()) {
+					println!("Wrong Model");
+					return;
+				}
+			}else {
+				unsat_case += 1;
+				if !verify_unsat(&clauses, var_n) {
+					println!("CNF is sat");
+					return;
+				}
+				break;
+			}
+		}
+	}
+	println!("\nTotal num of test: {}", num);
+	println!("Num of sat: {}\tNum of unsat: {}", sat_case, unsat_case);
+	println!("Test Passed");
+}
+
+fn random_core_test(num: usize) {
+	let mut rng = rand::thread_rng();
+	let mut unsat_case = 0;
+	for i in 1..num + 1 {
+		if i % 200 == 0 {
+			println!("Core test num: {}", i);
+		}
+		let config = SolverConfig {
+			core_tracking: 	true,
+			restart: 	Restart::Luby { unit: 1 },
+			..Default::default()
+		};
+		let mut solver = Solver::with_config(config);
+		let var_n = 10;		// number of variables
+		let lits = Lit::create_lits(&solver.create_vars(var_n));
+		random_clauses(&mut solver, lits.clone(), var_n as f32, 5., 50., 5., false);
+		let clauses = solver.get_oringin_clauses();
+		
+		let mut assumptions = Vec::<Lit>::new();
+		for _ in 0..(rng.next_f32() * 3.).floor() as usize {
+			let lit = lits[(rng.next_f32() * var_n as f32).floor() as usize];
+			assumptions.push(if rng.gen() {lit} else {!lit});
+		}
+		if solver.solve_with_assumptions(&assumptions) != SolveResult::Unsat {
+			continue;
+		}
+		unsat_case += 1;
+		
+		//the core and the MUS are UNSAT together with the failed assumptions
+		let with_failed = |indices: &[usize]| {
+			let mut subset: Vec<Clause> = indices.iter().map(|&ci| clauses[ci].clone()).collect();
+			for lit in solver.get_failed_assumptions() {
+				let mut clause = Clause::new();
+				clause.push(*lit);
+				subset.push(clause);
+			}
+			subset
+		};
+		let core = solver.get_unsat_core().unwrap();
+		if !verify_unsat(&with_failed(&core), var_n) {
+			println!("Core is sat");
+			return;
+		}
+		let mus = solver.get_mus().unwrap();
+		if mus.iter().any(|ci| !core.contains(ci)) || !verify_unsat(&with_failed(&mus), var_n) {
+			println!("MUS is sat");
+			return;
+		}
+		//removing any clause of the MUS makes it sat
+		for j in 0..mus.len() {
+			let mut subset = mus.clone();
+			subset.remove(j);
+			if verify_unsat(&with_failed(&subset), var_n) {
+				println!("MUS is not minimal");
+				return;
+			}
+		}
+	}
+	println!("\nTotal num of test: {}", num);
+	println!("Num of unsat: {}", unsat_case);
+	println!("Test Passed");
+}
+
+fn random_enumeration_test(num: usize) {
+	let mut total = 0;
+	for i in 1..num + 1 {
+		if i % 100 == 0 {
+			println!("Enumeration test num: {}", i);
+		}
+		//every other solver preprocesses the clauses, which may also remove the blocking clauses
+		let simplify = i % 2 == 0;
+		let config = SolverConfig { elimination: simplify, subsumption: simplify, blocked_clauses: simplify, ..Default::default() };
+		let mut solver = Solver::with_config(config);
+		let var_n = 10;		// number of variables
+		let vars = solver.create_vars(var_n);
+		random_clauses(&mut solver, Lit::create_lits(&vars), var_n as f32, 5., 30., 0., false);
+		let clauses = solver.get_oringin_clauses();
+		
+		//count the models and their projections on the first 4 variables by brute force
+		let mut count = 0;
+		let mut projected = HashSet::<usize>::new();
+		for j in 0..1 << var_n {
+			let model: Vec<VarValue> = (0..var_n).map(|k| if j >> k & 1 == 0 {VarValue::VTrue} else {VarValue::VFalse}).collect();
+			if verify(&clauses, &model) {
+				count += 1;
+				projected.insert(j & 0xf);
+			}
+		}
+		
+		let mut found = HashSet::<Vec<VarValue>>::new();
+		for model in solver.models(None, None) {
+			if !verify(&clauses, &model) || !found.insert(model) {
+				println!("Wrong model");
+				return;
+			}
+		}
+		let mut found_projected = HashSet::<Vec<VarValue>>::new();
+		for model in solver.models(Some(&vars[..4]), None) {
+			if !verify(&clauses, &model) || !found_projected.insert(model[..4].to_vec()) {
+				println!("Wrong projected model");
+				return;
+			}
+		}
+		let limited = solver.models(None, Some(3)).count();
+		if found.len() != count || found_projected.len() != projected.len() || limited != count.min(3) {
+			println!("Wrong number of models: {} {}", found.len(), count);
+			return;
+		}
+		//the guard variables and the blocking clauses are removed with the iterators
+		let sorted = |clauses: &[Clause]| -> Vec<Vec<Lit>> {
+			clauses.iter().map(|c| {
+				let mut lits = c.get_all_lits();
+				lits.sort();
+				lits
+			}).collect()
+		};
+		if !simplify && sorted(&solver.get_oringin_clauses()) != sorted(&clauses) {
+			println!("Blocking clauses are left");
+			return;
+		}
+		let sat = solver.solve() == SolveResult::Sat;
+		if sat != (count > 0) || (sat && (solver.get_model().len() != var_n || !verify(&clauses, solver.get_model()))) {
+			println!("Guard variables are left");
+			return;
+		}
+		total += count;
+	}
+	println!("\nTotal num of test: {}", num);
+	println!("Num of models: {}", total);
+	println!("Test Passed");
+}
+
+fn random_count_test(num: usize) {
+	let mut total = 0;
+	for i in 1..num + 1 {
+		if i % 500 == 0 {
+			println!("Model counting test num: {}", i);
+		}
+		let mut solver = Solver::new();
+		let var_n = 10;		// number of variables
+		let vars = solver.create_vars(var_n);
+		random_clauses(&mut solver, Lit::create_lits(&vars), var_n as f32, 4., 20., 3., false);
+		let clauses = solver.get_oringin_clauses();
+		
+		//count the models and their projections on the first 4 variables by brute force
+		let mut count = 0;
+		let mut projected = HashSet::<usize>::new();
+		for j in 0..1 << var_n {
+			let model: Vec<VarValue> = (0..var_n).map(|k| if j >> k & 1 == 0 {VarValue::VTrue} else {VarValue::VFalse}).collect();
+			if verify(&clauses, &model) {
+				count += 1;
+				projected.insert(j & 0xf);
+			}
+		}
+		
+		let counted = solver.count_models(None).to_u64();
+		let counted_projected = solver.count_models(Some(&vars[..4])).to_u64();
+		if counted != Some(count) || counted_projected != Some(projected.len() as u64) {
+			println!("Wrong number of models: {:?} {}, projected {:?} {}", counted, count, counted_projected, projected.len());
+			return;
+		}
+		total += count;
+	}
+	println!("\nTotal num of test: {}", num);
+	println!("Num of models: {}", total);
+	println!("Test Passed");
+}
+
+fn random_maxsat_test(num: usize) {
+	let mut rng = rand::thread_rng();
+	let mut total = 0;
+	for i in 1..num + 1 {
+		if i % 200 == 0 {
+			println!("MaxSAT test num: {}", i);
+		}
+		let mut solver = Solver::new();
+		let var_n = 10;		// number of variables
+		let vars = solver.create_vars(var_n);
+		let x = Lit::create_lits(&vars);
+		random_clauses(&mut solver, x.clone(), var_n as f32, 4., 10., 2., false);
+		let mut maxsat = MaxSatSolver::new();
+		maxsat.create_vars(var_n);
+		for clause in solver.get_oringin_clauses() {
+			maxsat.add_clause(clause);
+		}
+		for _ in 0..rng.gen_range(1, 30) {
+			let mut clause = Clause::new();
+			for _ in 0..rng.gen_range(1, 4) {
+				let lit = x[rng.gen_range(0, var_n)];
+				clause.push(if rng.gen() {lit} else {!lit});
+			}
+			maxsat.add_soft_clause(clause, rng.gen_range(1, 10));
+		}
+		
+		//find the minimum cost by brute force
+		let hard = maxsat.get_hard_clauses().to_vec();
+		let mut optimum = None;
+		for j in 0..1 << var_n {
+			let model: Vec<VarValue> = (0..var_n).map(|k| if j >> k & 1 == 0 {VarValue::VTrue} else {VarValue::VFalse}).collect();
+			if verify(&hard, &model) {
+				let cost = maxsat.cost_of(&model).unwrap();
+				optimum = Some(optimum.map_or(cost, |o: u64| o.min(cost)));
+			}
+		}
+		
+		//check both algorithms, and the instance read back from WCNF
+		let mut wcnf = Vec::<u8>::new();
+		maxsat.write_wcnf(&mut wcnf).unwrap();
+		let mut read = MaxSatSolver::read_wcnf(&wcnf[..]).unwrap();
+		let valid = |m: &MaxSatSolver| m.get_cost().is_none() || (verify(&hard, m.get_model()) && m.get_cost() == m.cost_of(m.get_model()));
+		let results = [
+			(maxsat.solve(MaxSatAlgorithm::LinearSearch).unwrap(), maxsat.get_cost(), valid(&maxsat)),
+			(maxsat.solve(MaxSatAlgorithm::CoreGuided).unwrap(), maxsat.get_cost(), valid(&maxsat)),
+			(read.solve(MaxSatAlgorithm::CoreGuided).unwrap(), read.get_cost(), valid(&read)),
+		];
+		for &(result, cost, valid) in &results {
+			let expected = if optimum.is_some() {SolveResult::Sat} else {SolveResult::Unsat};
+			if result != expected || cost != optimum || !valid {
+				println!("Wrong MaxSAT result: {} {:?}, expected {:?}", result, cost, optimum);
+				return;
+			}
+		}
+		if optimum.is_some() {
+			total += 1;
+		}
+	}
+	//the sum of the weights does not fit in u64
+	let wcnf = format!("p wcnf 1 2 {}\n{} 1 0\n{} -1 0\n", u64::MAX, u64::MAX - 1, u64::MAX - 1);
+	let mut maxsat = MaxSatSolver::new();
+	let x = Lit::create_lits(&maxsat.create_vars(1));
+	for &lit in &[x[0], !x[0]] {
+		let mut clause = Clause::new();
+		clause.push(lit);
+		maxsat.add_soft_clause(clause, u64::MAX - 1);
+	}
+	if MaxSatSolver::read_wcnf(wcnf.as_bytes()).is_ok() || maxsat.solve(MaxSatAlgorithm::CoreGuided).is_ok() {
+		println!("Overflowing weights are accepted");
+		return;
+	}
+	println!("\nTotal num of test: {}", num);
+	println!("Num of SAT: {}", total);
+	println!("Test Passed");
+}
+
+fn random_sls_test(num: usize) {
+	let algorithms = [SlsAlgorithm::WalkSat { noise: 0.567 }, SlsAlgorithm::ProbSat { cb: 2.5 }];
+	let mut total = 0;
+	for i in 1..num + 1 {
+		if i % 200 == 0 {
+			println!("Local search test num: {}", i);
+		}
+		let mut solver = Solver::new();
+		let var_n = 10;		// number of variables
+		let vars = solver.create_vars(var_n);
+		random_clauses(&mut solver, Lit::create_lits(&vars), var_n as f32, 5., 30., 3., false);
+		let clauses = solver.get_oringin_clauses();
+		let sat = solver.solve() == SolveResult::Sat;
+		
+		//local search never finds a model of an UNSAT CNF, and finds one of these small CNFs if it is SAT
+		for &algorithm in &algorithms {
+			let config = SlsConfig { algorithm, seed: i as u64, ..Default::default() };
+			let result = solver.local_search(config);
+			if (sat && (result != SolveResult::Sat || !verify(&clauses, solver.get_model()))) || (!sat && result == SolveResult::Sat) {
+				println!("Wrong local search result: {} {}", result, sat);
+				return;
+			}
+		}
+		if sat {
+			total += 1;
+		}
+	}
+	println!("\nTotal num of test: {}", num);
+	println!("Num of SAT: {}", total);
+	
+	//a large random 3-SAT CNF below the threshold
+	let var_n = 20000;
+	let mut rng = rand::thread_rng();
+	let mut solver = Solver::new();
+	let x = Lit::create_lits(&solver.create_vars(var_n));
+	for _ in 0..var_n * 4 {
+		let lits = (0..3).map(|_| {
+			let lit = x[rng.gen_range(0, var_n)];
+			if rng.gen() {lit} else {!lit}
+		}).collect();
+		solver.add_clause_from_lits(lits).unwrap_or(false);
+	}
+	let clauses = solver.get_oringin_clauses();
+	println!("\n\tVar num: {}\n\tClause num: {}\n", var_n, clauses.len());
+	for &algorithm in &algorithms {
+		let mut sls = LocalSearch::new(&clauses, var_n, SlsConfig { algorithm, max_flips: 100000000, ..Default::default() });
+		let start_time = now();
+		let result = sls.solve();
+		let duration = now() - start_time;
+		println!("{:?}: {} in {}.{:03} s, {} flips", algorithm, result, duration.num_seconds(), duration.num_milliseconds() % 1000, sls.get_flips());
+		if result == SolveResult::Sat && !verify(&clauses, sls.get_model()) {
+			println!("Wrong model");
+			return;
+		}
+	}
+	println!("Test Passed");
+}
+
+fn random_simplify_test(num: usize, config: SolverConfig) {
+	let mut rng = rand::thread_rng();
+	let mut sat_case = 0;
+	let mut unsat_case = 0;
+	for i in 1..num + 1 {
+		if i % 1000 == 0 {
+			println!("Preprocessing test num: {}", i);
+		}
+		let mut solver = Solver::with_config(config.clone());
+		let var_n = 10;		// number of variables
+		let lits = Lit::create_lits(&solver.create_vars(var_n));
+		let mut clauses = Vec::<Clause>::new();
+		
+		//add clauses in batches, so that the removed variables are used again, and solve under assumptions
+		//the models are checked against the clauses added, not those given back by the solver
+		for _ in 0..4 {
+			clauses.extend(random_clauses(&mut solver, lits.clone(), var_n as f32, 5., 10., 0.5, false));
+			
+			let mut assumptions = Vec::<Lit>::new();
+			for _ in 0..(rng.next_f32() * 3.).floor() as usize {
+				let lit = lits[(rng.next_f32() * var_n as f32).floor() as usize];
+				assumptions.push(if rng.gen() {lit} else {!lit});
+			}
+			
+			if solver.solve_with_assumptions(&assumptions) == SolveResult::Sat {
+				sat_case += 1;
+				let model = solver.get_model();
+				if !verify(&clauses, model) || assumptions.iter().any(|lit| lit.get_value() != model[lit.var_num()]) {
+					println!("Wrong Model");
+					return;
+				}
+			}else {
+				unsat_case += 1;
+				let mut with_failed = clauses.clone();
+				for lit in solver.get_failed_assumptions() {
+					let mut clause = Clause::new();
+					clause.push(*lit);
+					with_failed.push(clause);
+				}
+				if !verify_unsat(&with_failed, var_n) {
+					println!("Wrong failed assumptions");
+					return;
+				}
+			}
+		}
+		//the solver and the clauses it gives back must be satisfiable exactly when the clauses added are
+		let unsat = verify_unsat(&clauses, var_n);
+		if unsat != (solver.solve() == SolveResult::Unsat) || unsat != verify_unsat(&solver.get_oringin_clauses(), var_n) {
+			println!("CNF is changed");
+			return;
+		}
+	}
+	println!("\nTotal num of test: {}", num);
+	println!("Num of sat: {}\tNum of unsat: {}", sat_case, unsat_case);
+	println!("Test Passed");
+}
+
+fn budget_test() {
+	let mut solver = Solver::new();
+	pigeonhole(&mut solver, 9, 8);
+	
+	let budgets = vec![
+		("conflicts", Budget { conflicts: Some(100), ..Default::default() }),
+		("decisions", Budget { decisions: Some(100), ..Default::default() }),
+		("propagations", Budget { propagations: Some(1000), ..Default::default() }),
+		("time", Budget { time: Some(Duration::from_millis(1)), ..Default::default() }),
+	];
+	for (name, budget) in budgets {
+		solver.set_budget(budget);
+		let res = solver.solve();
+		println!("Budget on {}: {}", name, res);
+		if res != SolveResult::Unknown {
+			println!("Budget is not respected");
+			return;
+		}
+	}
+	
+	//the solver is still usable after running out of budget
+	solver.set_budget(Budget::default());
+	let res = solver.solve();
+	println!("No budget: {}", res);
+	if res != SolveResult::Unsat {
+		println!("Wrong result");
+		return;
+	}
+	println!("Test Passed");
+}
+
+fn interrupt_test() {
+	let mut solver = Solver::new();
+	pigeonhole(&mut solver, 12, 11);
+	
+	//stop the solver from a watchdog thread
+	let handle = solver.interrupt_handle();
+	let watchdog = thread::spawn(move || {
+		thread::sleep(Duration::from_millis(100));
+		handle.interrupt();
+	});
+	let res = solver.solve();
+	watchdog.join().unwrap();
+	println!("Interrupted solve: {}", res);
+	if res != SolveResult::Interrupted {
+		println!("Interrupt is ignored");
+		return;
+	}
+	
+	//the interrupt only stops one solve
+	solver.set_budget(Budget { conflicts: Some(100), ..Default::default() });
+	let res = solver.solve();
+	println!("Next solve: {}", res);
+	if res != SolveResult::Unknown {
+		println!("Wrong result");
+		return;
+	}
+	
+	//the budget and the handles created before a reset are kept
+	let handle = solver.interrupt_handle();
+	solver.reset();
+	let res = solver.solve();
+	println!("Solve after reset: {}", res);
+	if res != SolveResult::Unknown {
+		println!("Budget is not kept");
+		return;
+	}
+	handle.interrupt();
+	let res = solver.solve();
+	println!("Interrupted solve after reset: {}", res);
+	if res != SolveResult::Interrupted {
+		println!("Interrupt is ignored after reset");
+		return;
+	}
+	println!("Test Passed");
+}
+
+//stop the search after a number of conflicts, and print the progress
+struct ConflictLimit {
+	conflicts: 	u64,
+	limit: 		u64,
+}
+
+impl SolverObserver for ConflictLimit {
+	fn on_conflict(&mut self, _level: usize) {
+		self.conflicts += 1;
+	}
+	
+	fn on_progress(&mut self, stats: &SolverStats) {
+		println!("Conflicts: {}\tDecisions: {}\tRestarts: {}", stats.conflicts, stats.decisions, stats.restarts);
+	}
+	
+	fn should_stop(&mut self) -> bool {
+		self.conflicts >= self.limit
+	}
+}
+
+fn observer_test() {
+	let config = SolverConfig {
+		progress_interval: 	100,
+		..Default::default()
+	};
+	let mut solver = Solver::with_config(config);
+	pigeonhole(&mut solver, 10, 9);
+	
+	solver.set_observer(Box::new(ConflictLimit { conflicts: 0, limit: 500 }));
+	let res = solver.solve();
+	println!("Stopped solve: {}", res);
+	if res != SolveResult::Interrupted || solver.get_stats().conflicts < 500 {
+		println!("Observer is ignored");
+		return;
+	}
+	
+	//the search goes on without the observer
+	solver.take_observer();
+	let res = solver.solve();
+	println!("Next solve: {}", res);
+	if res != SolveResult::Unsat {
+		println!("Wrong result");
+		return;
+	}
+	println!("Test Passed");
+}
+
+fn proof_test(num: usize) {
+	let path = env::temp_dir().join("sat_proof_test.drat");
+	let mut rng = rand::thread_rng();
+	let mut unsat_case = 0;
+	for i in 0..num + 1 {
+		//the clauses derived by subsumption, probing and vivification are written into the proof
+		let mut solver = Solver::with_config(SolverConfig { subsumption: i % 4 < 2, probing: i % 2 == 0, vivify_interval: if i % 3 == 0 {50} else {0}, ..Default::default() });
+		if i == 0 {
+			pigeonhole(&mut solver, 7, 6);
+		}else {
+			//random 3-SAT around the threshold, so that about half of the instances are UNSAT
+			let var_n = 150;
+			let x = Lit::create_lits(&solver.create_vars(var_n));
+			for _ in 0..var_n * 426 / 100 {
+				let clause = (0..3).map(|_| if rng.gen() {x[rng.gen_range(0, var_n)]} else {!x[rng.gen_range(0, var_n)]}).collect();
+				solver.add_clause_from_lits(clause).unwrap_or(false);
+			}
+		}
+		let clauses = solver.get_oringin_clauses();
+		let format = if i % 2 == 0 {ProofFormat::Text} else {ProofFormat::Binary};
+		
+		let file = File::create(&path).unwrap();
+		solver.set_proof(Box::new(BufWriter::new(file)), format);
+		let res = solver.solve();
+		solver.close_proof().unwrap();
+		if res == SolveResult::Sat {
+			if !verify(&clauses, solver.get_model()) {
+				println!("Wrong Model");
+				return;
+			}
+			continue;
+		}
+		unsat_case += 1;
+		
+		let mut checker = ProofChecker::new(&clauses);
+		match checker.check(File::open(&path).unwrap(), format).unwrap() {
+			ProofResult::Verified => {},
+			res => {
+				println!("Wrong proof: {:?}", res);
+				return;
+			},
+		}
+		//the core is UNSAT by itself
+		let core = checker.get_core();
+		let mut solver = Solver::new();
+		for clause in core {
+			solver.add_clause(clause).unwrap_or(false);
+		}
+		if solver.solve() != SolveResult::Unsat {
+			println!("Core is sat");
+			return;
+		}
+	}
+	//a clause falsified when it is added gives the empty clause once
+	let mut solver = Solver::new();
+	let x = Lit::create_lits(&solver.create_vars(1));
+	solver.set_proof(Box::new(BufWriter::new(File::create(&path).unwrap())), ProofFormat::Text);
+	solver.add_clause_from_lits(vec![x[0]]).unwrap();
+	solver.add_clause_from_lits(vec![!x[0]]).unwrap();
+	solver.close_proof().unwrap();
+	if fs::read_to_string(&path).unwrap().lines().filter(|l| l.trim() == "0").count() != 1 {
+		println!("Empty clause is not written once");
+		return;
+	}
+	fs::remove_file(&path).unwrap();
+	println!("Total num of test: {}", num + 1);
+	println!("Num of verified proofs: {}", unsat_case);
+	println!("Test Passed");
+}
+
+//p pigeons in h holes, UNSAT if p > h
+fn pigeonhole(solver: &mut Solver, p: usize, h: usize) {
+	let x = Lit::create_lits(&solver.create_vars(p * h));
+	for i in 0..p {
+		solver.add_clause_from_lits((0..h).map(|j| x[i * h + j]).collect()).unwrap();
+	}
+	for j in 0..h {
+		for a in 0..p {
+			for b in a + 1..p {
+				solver.add_clause_from_lits(vec![!x[a * h + j], !x[b * h + j]]).unwrap();
+			}
+		}
+	}
+}
+
+fn random_efficiency_test(num: usize, branching: Branching) {
 	let var_n = 10000;		// number of variables
 	let clause_ms = 40.;	// max size of each clause
 	let clause_mn = 100000.;	// number of clauses
 	let assign_mn = 20.;	// number of assignments (unit clauses)
-	println!("\tVar num: {}\n\tMax clause num: {}\n\tMax clause size: {}\n\tMax assignment num: {}\n", var_n, clause_mn, clause_ms as usize, assign_mn as usize);
+	println!("\tBranching: {:?}\n\tVar num: {}\n\tMax clause num: {}\n\tMax clause size: {}\n\tMax assignment num: {}\n", branching, var_n, clause_mn, clause_ms as usize, assign_mn as usize);
 	
 	for i in 0..num {
-		let mut solver = Solver::new();
+		let config = SolverConfig {
+			branching,
+			..Default::default()
+		};
+		let mut solver = Solver::with_config(config);
 		{
 			
 			println!("Random Test: {}\n", i + 1);
 			
-			solver.set_iter_print_freq(var_n / 5);			 
-			
 			let mut lits = Vec::<Lit>::new();
 			
 			for i in solver.create_vars(var_n) {
@@ -135,14 +934,15 @@ fn random_efficiency_test(num: usize) {
 		println!("Solving...\n");
 		
 		let start_time = now();
-		let sat = solver.solve();
+		let sat = solver.solve() == SolveResult::Sat;
 		let end_time = now();
 		let duration = end_time - start_time;
 		print!("Total time: {}.{} s\t", duration.num_seconds(), duration.num_milliseconds() - duration.num_seconds() * 1000);
 		
 		print!("SAT: {}\t", sat);
 		let res = if sat {verify(&clauses, solver.get_model())} else {true};
-		println!("Result Match: {}\n\n------------------------------------------------\n", res);
+		println!("Result Match: {}\n", res);
+		println!("{}\n\n------------------------------------------------\n", solver.get_stats());
 		if !res {
 			println!("Error");
 			return;
@@ -186,8 +986,10 @@ fn verify(clauses: &[Clause], model: &[VarValue]) -> bool {
 	true
 }
 
-fn random_clauses(solver: &mut Solver, x: Vec<Lit>, lit_n: f32, clause_ms: f32, clause_mn: f32, assign_mn: f32, print: bool) {
+//add random clauses to the solver, and return them as they are generated
+fn random_clauses(solver: &mut Solver, x: Vec<Lit>, lit_n: f32, clause_ms: f32, clause_mn: f32, assign_mn: f32, print: bool) -> Vec<Clause> {
 	let mut rng = rand::thread_rng();
+	let mut added = Vec::<Clause>::new();
 	
 	let assign_n = (rng.next_f32() * assign_mn).floor() as usize;
 	let mut set = HashSet::<usize>::with_capacity(assign_n);
@@ -201,11 +1003,10 @@ fn random_clauses(solver: &mut Solver, x: Vec<Lit>, lit_n: f32, clause_ms: f32,
 				break;
 			}
 		}
-		if rng.gen() {
-			solver.add_clause_from_lits(vec![x[lit_num]]).unwrap();
-		}else {
-			solver.add_clause_from_lits(vec![!x[lit_num]]).unwrap();
-		}
+		let mut clause = Clause::new();
+		clause.push(if rng.gen() {x[lit_num]} else {!x[lit_num]});
+		added.push(clause.clone());
+		solver.add_clause(clause).unwrap_or(false);
 	}
 
 	let clause_n = (rng.next_f32() * clause_mn + 1. + clause_mn * 3.).floor() as usize / 4;
@@ -223,10 +1024,12 @@ fn random_clauses(solver: &mut Solver, x: Vec<Lit>, lit_n: f32, clause_ms: f32,
 			}
 		}
 //		println!("{}", clause);
-		solver.add_clause(clause).unwrap();
+		added.push(clause.clone());
+		solver.add_clause(clause).unwrap_or(false);
 	}
 	if print {
 		println!("\tClause num: {}\n\tTotal clause size: {}\n\tAssignment num: {}\n", clause_n, total_size, assign_n);
 	}
 //	println!("{}", solver);
+	added
 }
\ No newline at end of file